@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+use crate::BooleanFunctionTester;
+use crate::u32_tester::U32Tester;
+
+/// Representatives of the 48 extended-affine equivalence classes of 5-variable Boolean functions.
+pub const EQUIVALENCE_CLASSES: [u32; 48] = [0xaa55aa55, 0xaa55ab55, 0xaa55bb55, 0xaa5dbb55, 0xaaddbb55, 0xaa5dbb51, 0x2a5dbb51, 0xaaddbb51, 0x2a5dbf51, 0x6a5dbb51, 0x2addbb51, 0xa8ddbb51, 0xaeddda51, 0x0a5dbf51, 0x8addda51, 0xa8dd9b51, 0x88ddbb51, 0x88ddbb11, 0x8c5dda51, 0xa89d9b51, 0x8eddda51, 0xaefdda51, 0x025dbf51, 0x88ddda51, 0x88dd9b51, 0xceddda51, 0x0eddda51, 0x425dbf51, 0x8cddda51, 0x88dddb51, 0x289d9b51, 0x86fdda51, 0x88dddb71, 0xcefdda51, 0x0efdda51, 0x288d9b51, 0x8cfdda51, 0x8cdddb51, 0x8ccdda51, 0x289d9b41, 0x488ddb51, 0xccfdda51, 0x688d9b51, 0x288d9b41, 0x288d1b41, 0xdcfdda51, 0x68ad9b51, 0x688ddb51];
+
+const NUM_VARIABLES: usize = U32Tester::NUM_VARIABLES;
+const INPUTS_COUNT: usize = 1 << NUM_VARIABLES;
+
+/// Extended-affine transformation of 5-variable functions, mapping f to g(x) = f(A x + b) + l.x + c.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AffineTransformation {
+    /// Columns of the invertible matrix A over GF(2), column i being the image of the i-th unit vector.
+    pub matrix: [u32; NUM_VARIABLES],
+    pub b: u32,
+    pub l: u32,
+    pub c: bool,
+}
+
+impl AffineTransformation {
+    pub fn identity() -> Self {
+        AffineTransformation {
+            matrix: [1, 2, 4, 8, 16],
+            b: 0,
+            l: 0,
+            c: false,
+        }
+    }
+
+    /// Transformation mapping g back to f: f(y) = g(A^-1 y + A^-1 b) + (A^-T l).y + l.(A^-1 b) + c.
+    pub fn inverse(&self) -> Self {
+        let matrix = invert_matrix(&self.matrix);
+        let b = apply_matrix(&matrix, self.b);
+        AffineTransformation {
+            matrix,
+            b,
+            l: apply_matrix(&transpose_matrix(&matrix), self.l),
+            c: self.c ^ ((self.l & b).count_ones() % 2 == 1),
+        }
+    }
+
+    /// Truth table of g(x) = f(A x + b) + l.x + c.
+    pub fn apply(&self, rule_number: u32) -> u32 {
+        (0..INPUTS_COUNT as u32).fold(0, |transformed_rule_number, x| {
+            let value = U32Tester::compute_cellular_automata_rule(&rule_number, apply_matrix(&self.matrix, x) ^ self.b)
+                ^ ((self.l & x).count_ones() % 2 == 1)
+                ^ self.c;
+            transformed_rule_number | (value as u32) << x
+        })
+    }
+}
+
+/// Equivalence class of a rule, proven by an explicit transformation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Classification {
+    pub class_index: usize,
+    pub representative: u32,
+    /// Transformation mapping the representative to the rule: rule_number = witness.apply(representative).
+    pub witness: AffineTransformation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassificationError {
+    /// No representative has the same absolute Walsh and autocorrelation spectra as the rule.
+    NoMatchingSpectra,
+    /// Some representatives have the same spectra as the rule, but none of them is equivalent to it.
+    SpectraMatchWithoutEquivalence { matching_class_indices: Vec<usize> },
+}
+
+impl Display for ClassificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClassificationError::NoMatchingSpectra => write!(f, "no representative has matching spectra"),
+            ClassificationError::SpectraMatchWithoutEquivalence { matching_class_indices } => {
+                write!(f, "spectra match classes {:?} but the rule is equivalent to none of them", matching_class_indices)
+            }
+        }
+    }
+}
+
+impl Error for ClassificationError {}
+
+/// Class of the rule among EQUIVALENCE_CLASSES, with a transformation witness.
+pub fn classify(rule_number: u32) -> Result<Classification, ClassificationError> {
+    classify_with_invariants(rule_number, &EQUIVALENCE_CLASSES, equivalence_classes_invariants())
+}
+
+/// Class of the rule among the given representatives, with a transformation witness.
+pub fn classify_with_representatives(rule_number: u32, representatives: &[u32]) -> Result<Classification, ClassificationError> {
+    let invariants = representatives.iter()
+        .map(|representative| (U32Tester::absolute_walsh_spectrum(representative), U32Tester::absolute_autocorrelation_spectrum(representative)))
+        .collect::<Vec<SpectraInvariants>>();
+    classify_with_invariants(rule_number, representatives, &invariants)
+}
+
+fn classify_with_invariants(rule_number: u32, representatives: &[u32], invariants: &[SpectraInvariants]) -> Result<Classification, ClassificationError> {
+    let walsh_spectrum = U32Tester::absolute_walsh_spectrum(&rule_number);
+    let autocorrelation_spectrum = U32Tester::absolute_autocorrelation_spectrum(&rule_number);
+    let matching_class_indices = invariants.iter()
+        .enumerate()
+        .filter(|(_, (walsh_spectrum_eq, autocorrelation_spectrum_eq))| {
+            *walsh_spectrum_eq == walsh_spectrum && *autocorrelation_spectrum_eq == autocorrelation_spectrum
+        })
+        .map(|(class_index, _)| class_index)
+        .collect::<Vec<usize>>();
+    if matching_class_indices.is_empty() {
+        return Err(ClassificationError::NoMatchingSpectra);
+    }
+    matching_class_indices.iter()
+        .find_map(|&class_index| {
+            find_affine_equivalence(representatives[class_index], rule_number).map(|witness| Classification {
+                class_index,
+                representative: representatives[class_index],
+                witness,
+            })
+        })
+        .ok_or(ClassificationError::SpectraMatchWithoutEquivalence { matching_class_indices })
+}
+
+/// Finds an extended-affine transformation T such that g = T(f), if f and g are equivalent.
+pub fn find_affine_equivalence(f: u32, g: u32) -> Option<AffineTransformation> {
+    let walsh_spectrum_f = U32Tester::walsh_spectrum(&f);
+    let walsh_spectrum_g = U32Tester::walsh_spectrum(&g);
+    if U32Tester::absolute_walsh_spectrum(&f) != U32Tester::absolute_walsh_spectrum(&g) {
+        return None;
+    }
+    // If g(x) = f(A x + b) + l.x + c, then W_g(w) = (-1)^(c + b.u) W_f(u) with u = M(w) = A^-T (w + l):
+    // the affine map M is searched for one basis vector image at a time, then A, b, l and c are deduced.
+    let mut walsh_map = [0u32; INPUTS_COUNT];
+    (0..INPUTS_COUNT as u32)
+        .filter(|&m| walsh_spectrum_f[m as usize].unsigned_abs() == walsh_spectrum_g[0].unsigned_abs())
+        .find_map(|m| {
+            walsh_map[0] = m;
+            search_walsh_map(f, g, &walsh_spectrum_f, &walsh_spectrum_g, &mut walsh_map, 0)
+        })
+}
+
+/// The canonical representative of a 5-variable function is the element of EQUIVALENCE_CLASSES in its class.
+pub fn canonical_affine_representative(rule_number: u32) -> u32 {
+    canonical_affine_form(rule_number).0
+}
+
+/// Canonical representative of the function, along with the transformation T such that representative = T(rule_number).
+pub fn canonical_affine_form(rule_number: u32) -> (u32, AffineTransformation) {
+    match classify(rule_number) {
+        Ok(classification) => (classification.representative, classification.witness.inverse()),
+        Err(error) => panic!("No representative of {:#010x} in EQUIVALENCE_CLASSES: {}", rule_number, error),
+    }
+}
+
+/// Absolute Walsh and absolute autocorrelation spectra, both invariant under extended-affine equivalence.
+type SpectraInvariants = (HashMap<u32, usize>, HashMap<u32, usize>);
+
+/// Invariants of each representative, computed once.
+fn equivalence_classes_invariants() -> &'static [SpectraInvariants] {
+    static INVARIANTS: OnceLock<Vec<SpectraInvariants>> = OnceLock::new();
+    INVARIANTS.get_or_init(|| {
+        EQUIVALENCE_CLASSES.iter()
+            .map(|representative| (U32Tester::absolute_walsh_spectrum(representative), U32Tester::absolute_autocorrelation_spectrum(representative)))
+            .collect()
+    })
+}
+
+/// walsh_map holds M(w) for w < 2^k, M(0) + span(M(w) + M(0)) being spanned by the images of the first k unit vectors.
+fn search_walsh_map(f: u32, g: u32, walsh_spectrum_f: &[i32], walsh_spectrum_g: &[i32], walsh_map: &mut [u32; INPUTS_COUNT], k: usize) -> Option<AffineTransformation> {
+    let span_size = 1usize << k;
+    if k == NUM_VARIABLES {
+        return transformation_from_walsh_map(f, g, walsh_map);
+    }
+    let m = walsh_map[0];
+    for v in 0..INPUTS_COUNT as u32 {
+        // The image of the k-th unit vector must be linearly independent of the previous ones
+        if walsh_map[..span_size].iter().any(|&image| image ^ m == v) {
+            continue;
+        }
+        let mut sign_ratio: Option<bool> = None;
+        let consistent = (0..span_size).all(|w| {
+            let (walsh_value_g, new_walsh_value_g) = (walsh_spectrum_g[w], walsh_spectrum_g[w | span_size]);
+            let (walsh_value_f, new_walsh_value_f) = (walsh_spectrum_f[walsh_map[w] as usize], walsh_spectrum_f[(walsh_map[w] ^ v) as usize]);
+            if new_walsh_value_g.unsigned_abs() != new_walsh_value_f.unsigned_abs() {
+                return false;
+            }
+            if walsh_value_g == 0 || new_walsh_value_g == 0 {
+                return true;
+            }
+            // (-1)^(b.M(w)) must be affine, its ratio along the k-th unit vector is constant
+            let ratio = ((walsh_value_g < 0) ^ (walsh_value_f < 0)) ^ ((new_walsh_value_g < 0) ^ (new_walsh_value_f < 0));
+            *sign_ratio.get_or_insert(ratio) == ratio
+        });
+        if !consistent {
+            continue;
+        }
+        for w in 0..span_size {
+            walsh_map[w | span_size] = walsh_map[w] ^ v;
+        }
+        if let Some(transformation) = search_walsh_map(f, g, walsh_spectrum_f, walsh_spectrum_g, walsh_map, k + 1) {
+            return Some(transformation);
+        }
+    }
+    None
+}
+
+fn transformation_from_walsh_map(f: u32, g: u32, walsh_map: &[u32; INPUTS_COUNT]) -> Option<AffineTransformation> {
+    let m = walsh_map[0];
+    let linear_part: [u32; NUM_VARIABLES] = std::array::from_fn(|i| walsh_map[1 << i] ^ m);
+    // M(w) = L w + m with L = A^-T and m = L l
+    let linear_part_inverse = invert_matrix(&linear_part);
+    let matrix = invert_matrix(&transpose_matrix(&linear_part));
+    let l = apply_matrix(&linear_part_inverse, m);
+    (0..INPUTS_COUNT as u32)
+        .flat_map(|b| [false, true].map(|c| AffineTransformation { matrix, b, l, c }))
+        .find(|transformation| transformation.apply(f) == g)
+}
+
+fn apply_matrix(matrix: &[u32; NUM_VARIABLES], x: u32) -> u32 {
+    matrix.iter()
+        .enumerate()
+        .filter(|(i, _)| x & (1 << i) != 0)
+        .fold(0, |image, (_, &column)| image ^ column)
+}
+
+fn transpose_matrix(matrix: &[u32; NUM_VARIABLES]) -> [u32; NUM_VARIABLES] {
+    std::array::from_fn(|j| {
+        matrix.iter()
+            .enumerate()
+            .fold(0, |column, (i, &matrix_column)| column | ((matrix_column >> j) & 1) << i)
+    })
+}
+
+fn invert_matrix(matrix: &[u32; NUM_VARIABLES]) -> [u32; NUM_VARIABLES] {
+    let mut inverse = [0u32; NUM_VARIABLES];
+    for x in 0..INPUTS_COUNT as u32 {
+        let image = apply_matrix(matrix, x);
+        if image.is_power_of_two() {
+            inverse[image.trailing_zeros() as usize] = x;
+        }
+    }
+    inverse
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::BooleanFunctionTester;
+    use crate::u32_tester::U32Tester;
+    use super::{canonical_affine_form, canonical_affine_representative, classify, classify_with_representatives, find_affine_equivalence, AffineTransformation, ClassificationError, EQUIVALENCE_CLASSES};
+
+    /// Deterministic pseudo-random sequence, to avoid adding a dependency for tests
+    fn xorshift(state: &mut u32) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        *state
+    }
+
+    fn random_invertible_matrix(state: &mut u32) -> [u32; 5] {
+        loop {
+            let matrix: [u32; 5] = std::array::from_fn(|_| xorshift(state) & 31);
+            if (0..32u32).map(|x| super::apply_matrix(&matrix, x)).collect::<HashSet<u32>>().len() == 32 {
+                return matrix;
+            }
+        }
+    }
+
+    #[test]
+    fn test_affine_transformation() {
+        assert_eq!(AffineTransformation::identity().apply(3755921403), 3755921403);
+        // Swap x0 and x1, then add x2 + 1
+        let transformation = AffineTransformation { matrix: [2, 1, 4, 8, 16], b: 0, l: 4, c: true };
+        assert_eq!(transformation.apply(0x88888888), !(0x88888888u32 ^ 0xf0f0f0f0));
+        assert_eq!(transformation.apply(0xaaaaaaaa), !(0xcccccccc ^ 0xf0f0f0f0));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let mut state = 0xcafebabe;
+        for _ in 0..50 {
+            let matrix = random_invertible_matrix(&mut state);
+            let transformation = AffineTransformation { matrix, b: xorshift(&mut state) & 31, l: xorshift(&mut state) & 31, c: xorshift(&mut state) & 1 == 1 };
+            let rule_number = xorshift(&mut state);
+            assert_eq!(transformation.inverse().apply(transformation.apply(rule_number)), rule_number);
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let mut state = 0x2545f491;
+        for _ in 0..100 {
+            let rule_number = xorshift(&mut state);
+            let classification = classify(rule_number).unwrap();
+            assert_eq!(classification.representative, EQUIVALENCE_CLASSES[classification.class_index]);
+            assert_eq!(classification.witness.apply(classification.representative), rule_number);
+        }
+        // 0x66666666 (x0 + x1) is affine, hence equivalent to the first representative only
+        let classification = classify_with_representatives(0x66666666, &EQUIVALENCE_CLASSES[..2]).unwrap();
+        assert_eq!(classification.class_index, 0);
+        assert_eq!(classify_with_representatives(0x66666666, &EQUIVALENCE_CLASSES[1..]), Err(ClassificationError::NoMatchingSpectra));
+    }
+
+    #[test]
+    fn test_equivalence_classes_invariants_are_distinct() {
+        let invariants = EQUIVALENCE_CLASSES.iter().map(|representative| {
+            let mut walsh_spectrum = U32Tester::absolute_walsh_spectrum(representative).into_iter().collect::<Vec<(u32, usize)>>();
+            let mut autocorrelation_spectrum = U32Tester::absolute_autocorrelation_spectrum(representative).into_iter().collect::<Vec<(u32, usize)>>();
+            walsh_spectrum.sort();
+            autocorrelation_spectrum.sort();
+            (walsh_spectrum, autocorrelation_spectrum)
+        }).collect::<HashSet<_>>();
+        assert_eq!(invariants.len(), 48);
+    }
+
+    #[test]
+    fn test_find_affine_equivalence() {
+        let mut state = 0x12345678;
+        for &representative in EQUIVALENCE_CLASSES.iter() {
+            assert_eq!(canonical_affine_representative(representative), representative);
+            for _ in 0..4 {
+                let matrix = random_invertible_matrix(&mut state);
+                let transformation = AffineTransformation { matrix, b: xorshift(&mut state) & 31, l: xorshift(&mut state) & 31, c: xorshift(&mut state) & 1 == 1 };
+                let rule_number = transformation.apply(representative);
+                let found_transformation = find_affine_equivalence(representative, rule_number).unwrap();
+                assert_eq!(found_transformation.apply(representative), rule_number);
+                let (canonical_representative, canonical_transformation) = canonical_affine_form(rule_number);
+                assert_eq!(canonical_representative, representative);
+                assert_eq!(canonical_transformation.apply(rule_number), representative);
+            }
+        }
+        assert_eq!(find_affine_equivalence(EQUIVALENCE_CLASSES[0], EQUIVALENCE_CLASSES[1]), None);
+    }
+
+    #[test]
+    fn test_canonical_affine_representative() {
+        let mut state = 0x9e3779b9;
+        for _ in 0..200 {
+            let rule_number = xorshift(&mut state);
+            let (representative, transformation) = canonical_affine_form(rule_number);
+            assert!(EQUIVALENCE_CLASSES.contains(&representative));
+            assert_eq!(transformation.apply(rule_number), representative);
+        }
+    }
+}
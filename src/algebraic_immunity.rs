@@ -0,0 +1,64 @@
+/// Returns the algebraic immunity of the function given by its truth table, along with the truth table
+/// of a nonzero annihilator of minimum degree (annihilating either the function or its complement).
+pub(crate) fn minimum_degree_annihilator(num_variables: usize, truth_table: &[bool]) -> (usize, Vec<bool>) {
+    for degree in 0..=num_variables {
+        let monomials = (0..1u32 << num_variables)
+            .filter(|monomial| monomial.count_ones() as usize <= degree)
+            .collect::<Vec<u32>>();
+        for annihilated_value in [true, false] {
+            // g annihilates f (resp. f + 1) iff g(x) = 0 for every x in the support of f (resp. f + 1)
+            let monomial_evaluation_matrix = (0..1u32 << num_variables)
+                .filter(|&x| truth_table[x as usize] == annihilated_value)
+                .map(|x| {
+                    let mut row = vec![0u64; monomials.len().div_ceil(64)];
+                    for (column, &monomial) in monomials.iter().enumerate() {
+                        if x & monomial == monomial {
+                            row[column / 64] |= 1 << (column % 64);
+                        }
+                    }
+                    row
+                })
+                .collect::<Vec<Vec<u64>>>();
+            if let Some(anf_coefficients) = find_kernel_vector(monomial_evaluation_matrix, monomials.len()) {
+                let annihilator = (0..1u32 << num_variables)
+                    .map(|x| {
+                        monomials.iter()
+                            .zip(anf_coefficients.iter())
+                            .filter(|(&monomial, &coefficient)| coefficient && x & monomial == monomial)
+                            .count() % 2 == 1
+                    })
+                    .collect();
+                return (degree, annihilator);
+            }
+        }
+    }
+    unreachable!("The constant function 1 always annihilates either f or f + 1 at degree num_variables")
+}
+
+/// Gaussian elimination over GF(2), returns a nonzero vector of the kernel if there is one.
+fn find_kernel_vector(mut matrix: Vec<Vec<u64>>, columns_count: usize) -> Option<Vec<bool>> {
+    let mut pivot_columns: Vec<usize> = Vec::new();
+    for column in 0..columns_count {
+        let (word, bit) = (column / 64, 1u64 << (column % 64));
+        let rank = pivot_columns.len();
+        let pivot_row = match (rank..matrix.len()).find(|&row| matrix[row][word] & bit != 0) {
+            Some(pivot_row) => pivot_row,
+            None => continue,
+        };
+        matrix.swap(rank, pivot_row);
+        let pivot = matrix[rank].clone();
+        for (row_index, row) in matrix.iter_mut().enumerate() {
+            if row_index != rank && row[word] & bit != 0 {
+                row.iter_mut().zip(pivot.iter()).for_each(|(row_word, pivot_word)| *row_word ^= pivot_word);
+            }
+        }
+        pivot_columns.push(column);
+    }
+    let free_column = (0..columns_count).find(|column| !pivot_columns.contains(column))?;
+    let mut kernel_vector = vec![false; columns_count];
+    kernel_vector[free_column] = true;
+    for (row, &pivot_column) in pivot_columns.iter().enumerate() {
+        kernel_vector[pivot_column] = matrix[row][free_column / 64] & (1 << (free_column % 64)) != 0;
+    }
+    Some(kernel_vector)
+}
@@ -0,0 +1,360 @@
+use std::fmt::{Display, Formatter};
+use crate::BooleanFunctionTester;
+use crate::vectorial::VectorialFunction;
+
+/// Value of the neighbours lying beyond the ends of the ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryCondition {
+    /// Cell indices wrap around modulo the ring size.
+    Periodic,
+    /// Cells beyond the ends are 0.
+    Null,
+    /// The ring is mirrored at its ends, cell -1 is cell 0 and cell ring_size is cell ring_size - 1.
+    Reflective,
+    /// Cells beyond the ends have the given constant value.
+    Fixed(bool),
+}
+
+impl BoundaryCondition {
+    pub const ALL: [BoundaryCondition; 5] = [BoundaryCondition::Periodic, BoundaryCondition::Null, BoundaryCondition::Reflective, BoundaryCondition::Fixed(false), BoundaryCondition::Fixed(true)];
+}
+
+impl Display for BoundaryCondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundaryCondition::Periodic => write!(f, "periodic"),
+            BoundaryCondition::Null => write!(f, "null"),
+            BoundaryCondition::Reflective => write!(f, "reflective"),
+            BoundaryCondition::Fixed(value) => write!(f, "fixed {}", *value as u8),
+        }
+    }
+}
+
+/// Rule applied to each cell at each iteration, non-uniform schedules describe hybrid cellular automata.
+#[derive(Debug, Clone, Copy)]
+pub enum RuleSchedule<'a, Rule> {
+    /// Same rule for every cell and every iteration.
+    Uniform(&'a Rule),
+    /// One rule per cell position, applied at every iteration.
+    PerCell(&'a [Rule]),
+    /// Rules applied to every cell, cycling through the list at each iteration.
+    PerRound(&'a [Rule]),
+    /// Rules per cell position indexed [round][cell], cycling through the rounds at each iteration.
+    PerCellPerRound(&'a [Vec<Rule>]),
+}
+
+impl<Rule> RuleSchedule<'_, Rule> {
+    pub fn rule(&self, round: usize, cell: usize) -> &Rule {
+        match self {
+            RuleSchedule::Uniform(rule) => rule,
+            RuleSchedule::PerCell(rules) => &rules[cell],
+            RuleSchedule::PerRound(rules) => &rules[round % rules.len()],
+            RuleSchedule::PerCellPerRound(rules) => &rules[round % rules.len()][cell],
+        }
+    }
+}
+
+/// Extends a cellular automata rule of 2 * radius + 1 variables to a Boolean function of ring_size variables:
+/// the function input is the initial state of a ring of cells, the rule is applied to every cell for a number
+/// of iterations, and the function output is the final state of the observed cell.
+pub struct CaExtender {
+    ring_size: usize,
+    radius: usize,
+    iterations: usize,
+    observed_cell: usize,
+    boundary_condition: BoundaryCondition,
+}
+
+impl CaExtender {
+    pub fn new(ring_size: usize, radius: usize, iterations: usize, observed_cell: usize) -> Self {
+        if ring_size == 0 || ring_size > 16 {
+            panic!("Ring size must be between 1 and 16");
+        }
+        if observed_cell >= ring_size {
+            panic!("Observed cell must be less than the ring size {}", ring_size);
+        }
+        CaExtender {
+            ring_size,
+            radius,
+            iterations,
+            observed_cell,
+            boundary_condition: BoundaryCondition::Periodic,
+        }
+    }
+
+    pub fn with_boundary_condition(mut self, boundary_condition: BoundaryCondition) -> Self {
+        self.boundary_condition = boundary_condition;
+        self
+    }
+
+    pub fn ring_size(&self) -> usize {
+        self.ring_size
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn observed_cell(&self) -> usize {
+        self.observed_cell
+    }
+
+    pub fn boundary_condition(&self) -> BoundaryCondition {
+        self.boundary_condition
+    }
+
+    /// Extended function, as the rule_number representation of the tester T of ring_size variables.
+    pub fn extend<R: BooleanFunctionTester, T: BooleanFunctionTester>(&self, rule_number: &R::UnsignedRepr) -> T::UnsignedRepr {
+        self.extend_hybrid::<R, T>(&RuleSchedule::Uniform(rule_number))
+    }
+
+    /// Extended function truth table, indexed by the initial ring state (bit i of the index is cell i).
+    pub fn extend_truth_table<R: BooleanFunctionTester>(&self, rule_number: &R::UnsignedRepr) -> Vec<bool> {
+        self.extend_hybrid_truth_table::<R>(&RuleSchedule::Uniform(rule_number))
+    }
+
+    /// Extended function of a hybrid cellular automaton, as the rule_number representation of the tester T of ring_size variables.
+    pub fn extend_hybrid<R: BooleanFunctionTester, T: BooleanFunctionTester>(&self, rule_schedule: &RuleSchedule<R::UnsignedRepr>) -> T::UnsignedRepr {
+        if T::NUM_VARIABLES != self.ring_size {
+            panic!("Output tester must have {} variables", self.ring_size);
+        }
+        T::from_truth_table(&self.extend_hybrid_truth_table::<R>(rule_schedule))
+    }
+
+    pub fn extend_hybrid_truth_table<R: BooleanFunctionTester>(&self, rule_schedule: &RuleSchedule<R::UnsignedRepr>) -> Vec<bool> {
+        self.evolve_rings::<R, bool>(rule_schedule, |ring| ring[self.observed_cell])
+    }
+
+    /// Whole ring after the iterations, as a vectorial function GF(2)^ring_size -> GF(2)^ring_size
+    /// (bit i of both the input and the output is cell i). The observed cell is ignored.
+    pub fn extend_vectorial<R: BooleanFunctionTester>(&self, rule_number: &R::UnsignedRepr) -> VectorialFunction {
+        self.extend_hybrid_vectorial::<R>(&RuleSchedule::Uniform(rule_number))
+    }
+
+    pub fn extend_hybrid_vectorial<R: BooleanFunctionTester>(&self, rule_schedule: &RuleSchedule<R::UnsignedRepr>) -> VectorialFunction {
+        let lookup_table = self.evolve_rings::<R, u32>(rule_schedule, |ring| {
+            ring.iter()
+                .enumerate()
+                .fold(0, |output, (i, &cell)| output | (cell as u32) << i)
+        });
+        VectorialFunction::new(self.ring_size, self.ring_size, lookup_table)
+    }
+
+    /// Global map of a single application of the rule to the ring, regardless of the number of iterations.
+    pub fn ring_map<R: BooleanFunctionTester>(&self, rule_number: &R::UnsignedRepr) -> VectorialFunction {
+        CaExtender {
+            iterations: 1,
+            ..*self
+        }.extend_vectorial::<R>(rule_number)
+    }
+
+    /// Whether the global ring map is a permutation of GF(2)^ring_size.
+    pub fn is_reversible<R: BooleanFunctionTester>(&self, rule_number: &R::UnsignedRepr) -> bool {
+        self.ring_map::<R>(rule_number).is_permutation()
+    }
+
+    /// Iterates the cellular automaton from every initial ring state, and observes each final ring.
+    fn evolve_rings<R: BooleanFunctionTester, O>(&self, rule_schedule: &RuleSchedule<R::UnsignedRepr>, observe: impl Fn(&[bool]) -> O) -> Vec<O> {
+        self.check_rule_tester::<R>();
+        self.check_rule_schedule(rule_schedule);
+        let mut ring = vec![false; self.ring_size];
+        let mut new_ring = vec![false; self.ring_size];
+        (0usize..(1 << self.ring_size)).map(|input| {
+            for (i, cell) in ring.iter_mut().enumerate() {
+                *cell = (input & (1 << i)) != 0;
+            }
+            for round in 0..self.iterations {
+                self.update_ring::<R>(&ring, &mut new_ring, rule_schedule, round);
+                std::mem::swap(&mut ring, &mut new_ring);
+            }
+            observe(&ring)
+        }).collect()
+    }
+
+    /// Applies the rule once to every cell of the ring.
+    pub fn get_new_ring<R: BooleanFunctionTester>(&self, ring: &[bool], rule_number: &R::UnsignedRepr) -> Vec<bool> {
+        self.check_rule_tester::<R>();
+        if ring.len() != self.ring_size {
+            panic!("Ring must have {} cells", self.ring_size);
+        }
+        let mut new_ring = vec![false; self.ring_size];
+        self.update_ring::<R>(ring, &mut new_ring, &RuleSchedule::Uniform(rule_number), 0);
+        new_ring
+    }
+
+    fn update_ring<R: BooleanFunctionTester>(&self, ring: &[bool], new_ring: &mut [bool], rule_schedule: &RuleSchedule<R::UnsignedRepr>, round: usize) {
+        for (i, new_cell) in new_ring.iter_mut().enumerate() {
+            // Leftmost neighbour is the most significant input bit
+            let input_bits = (0..=2 * self.radius).fold(0u32, |input_bits, neighbour| {
+                let cell = self.neighbour_value(ring, i as isize + neighbour as isize - self.radius as isize);
+                input_bits << 1 | cell as u32
+            });
+            *new_cell = R::compute_cellular_automata_rule(rule_schedule.rule(round, i), input_bits);
+        }
+    }
+
+    fn neighbour_value(&self, ring: &[bool], position: isize) -> bool {
+        let ring_size = self.ring_size as isize;
+        if (0..ring_size).contains(&position) {
+            return ring[position as usize];
+        }
+        match self.boundary_condition {
+            BoundaryCondition::Periodic => ring[position.rem_euclid(ring_size) as usize],
+            BoundaryCondition::Null => false,
+            BoundaryCondition::Reflective => {
+                let mirrored_position = position.rem_euclid(2 * ring_size);
+                if mirrored_position < ring_size {
+                    ring[mirrored_position as usize]
+                } else {
+                    ring[(2 * ring_size - 1 - mirrored_position) as usize]
+                }
+            }
+            BoundaryCondition::Fixed(value) => value,
+        }
+    }
+
+    fn check_rule_schedule<Rule>(&self, rule_schedule: &RuleSchedule<Rule>) {
+        let schedule_ok = match rule_schedule {
+            RuleSchedule::Uniform(_) => true,
+            RuleSchedule::PerCell(rules) => rules.len() == self.ring_size,
+            RuleSchedule::PerRound(rules) => !rules.is_empty(),
+            RuleSchedule::PerCellPerRound(rules) => !rules.is_empty() && rules.iter().all(|round_rules| round_rules.len() == self.ring_size),
+        };
+        if !schedule_ok {
+            panic!("Rule schedule must have at least one round of rules, and {} rules per cell", self.ring_size);
+        }
+    }
+
+    fn check_rule_tester<R: BooleanFunctionTester>(&self) {
+        if R::NUM_VARIABLES != 2 * self.radius + 1 {
+            panic!("Rule tester must have {} variables for radius {}", 2 * self.radius + 1, self.radius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use numext_fixed_uint::U512;
+    use crate::BooleanFunctionTester;
+    use crate::generic_tester::GenericTester;
+    use crate::u32_tester::U32Tester;
+    use crate::u512_tester::U512Tester;
+    use super::{BoundaryCondition, CaExtender, RuleSchedule};
+
+    #[test]
+    fn test_extend_5_to_9() {
+        let ca_extender = CaExtender::new(9, 2, 2, 4);
+        assert_eq!(ca_extender.extend::<U32Tester, U512Tester>(&30), U512::from_dec_str("37460982766603781020825907715008012597628564512828165327878621418759283977002975347478812144838918388274817822689670664405426424964444627290292224").unwrap());
+        assert_eq!(ca_extender.extend::<U32Tester, U512Tester>(&3755921403), U512::from_dec_str("13407807929942597098847186317233203207567774193617110470060917943962159749266320668916332720419656517166240687192852651607093788182615166684716733579591935").unwrap());
+        assert_eq!(ca_extender.extend::<U32Tester, U512Tester>(&0x8ccdda51), U512::from_dec_str("13354712834134303273560529399259098145840191183808588933533266384079519850117494153654293809118084079592020832455354014427601683644543119780093496121129823").unwrap());
+    }
+
+    #[test]
+    fn test_get_new_ring() {
+        let ca_extender = CaExtender::new(3, 1, 1, 1);
+        // Rule 30 of radius 1: left XOR (center OR right)
+        let rule_30 = vec![30u64];
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<3>>(&[true, false, false], &rule_30), vec![true, true, true]);
+        assert_eq!(ca_extender.extend::<GenericTester<3>, GenericTester<3>>(&rule_30), GenericTester::<3>::from_truth_table(&(0..8u32).map(|x| {
+            let (left, center, right) = (x & 1 != 0, x & 2 != 0, x & 4 != 0);
+            left ^ (center || right)
+        }).collect::<Vec<bool>>()));
+    }
+
+    #[test]
+    fn test_boundary_conditions() {
+        let ca_extender = CaExtender::new(3, 1, 1, 1);
+        // Rule 90 of radius 1: left XOR right
+        let rule_90 = vec![90u64];
+        let ring = [true, false, false];
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<3>>(&ring, &rule_90), vec![false, true, true]);
+        let ca_extender = ca_extender.with_boundary_condition(BoundaryCondition::Null);
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<3>>(&ring, &rule_90), vec![false, true, false]);
+        let ca_extender = ca_extender.with_boundary_condition(BoundaryCondition::Fixed(true));
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<3>>(&ring, &rule_90), vec![true, true, true]);
+        let ca_extender = ca_extender.with_boundary_condition(BoundaryCondition::Reflective);
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<3>>(&ring, &rule_90), vec![true, true, false]);
+
+        // Radius larger than the ring, the reflection is applied repeatedly
+        let ca_extender = CaExtender::new(2, 2, 1, 0).with_boundary_condition(BoundaryCondition::Reflective);
+        // Neighbourhood of cell 0 in ring [a, b] is [b, a, a, b, b], the rule returns the leftmost neighbour
+        let leftmost_neighbour_rule = vec![0xffff0000u64];
+        assert_eq!(ca_extender.get_new_ring::<GenericTester<5>>(&[false, true], &leftmost_neighbour_rule), vec![true, false]);
+    }
+
+    #[test]
+    fn test_hybrid_extension() {
+        let ca_extender = CaExtender::new(9, 2, 2, 4);
+        assert_eq!(ca_extender.extend_hybrid::<U32Tester, U512Tester>(&RuleSchedule::Uniform(&3755921403)), ca_extender.extend::<U32Tester, U512Tester>(&3755921403));
+        assert_eq!(ca_extender.extend_hybrid::<U32Tester, U512Tester>(&RuleSchedule::PerCell(&[3755921403; 9])), ca_extender.extend::<U32Tester, U512Tester>(&3755921403));
+        assert_eq!(ca_extender.extend_hybrid::<U32Tester, U512Tester>(&RuleSchedule::PerCellPerRound(&[vec![30; 9], vec![30; 9]])), ca_extender.extend::<U32Tester, U512Tester>(&30));
+
+        // Rule 204 of radius 1 returns the center cell, applying it first is the same as a single round of rule 90
+        let (rule_90, rule_204) = (vec![90u64], vec![204u64]);
+        let ca_extender = CaExtender::new(7, 1, 2, 3);
+        assert_eq!(ca_extender.extend_hybrid::<GenericTester<3>, GenericTester<7>>(&RuleSchedule::PerRound(&[rule_204.clone(), rule_90.clone()])), CaExtender::new(7, 1, 1, 3).extend::<GenericTester<3>, GenericTester<7>>(&rule_90));
+        // Alternating rules cycle through the schedule for any number of iterations
+        let alternating_rules = [rule_204.clone(), rule_90.clone()];
+        assert_eq!(CaExtender::new(7, 1, 4, 3).extend_hybrid::<GenericTester<3>, GenericTester<7>>(&RuleSchedule::PerRound(&alternating_rules)),
+                   CaExtender::new(7, 1, 2, 3).extend::<GenericTester<3>, GenericTester<7>>(&rule_90));
+
+        // Only the observed cell applies rule 90, the extension after one round is then x2 + x4
+        let mut per_cell_rules = vec![rule_204; 7];
+        per_cell_rules[3] = rule_90;
+        let extended_function = CaExtender::new(7, 1, 1, 3).extend_hybrid::<GenericTester<3>, GenericTester<7>>(&RuleSchedule::PerCell(&per_cell_rules));
+        assert_eq!(extended_function, GenericTester::<7>::from_truth_table(&(0..1u32 << 7).map(|x| (x >> 2) & 1 != (x >> 4) & 1).collect::<Vec<bool>>()));
+    }
+
+    #[test]
+    fn test_vectorial_extension() {
+        let ca_extender = CaExtender::new(9, 2, 2, 4);
+        let vectorial_function = ca_extender.extend_vectorial::<U32Tester>(&3755921403);
+        assert_eq!(vectorial_function.num_inputs(), 9);
+        assert_eq!(vectorial_function.num_outputs(), 9);
+        assert_eq!(vectorial_function.coordinate_function::<U512Tester>(4), ca_extender.extend::<U32Tester, U512Tester>(&3755921403));
+        for cell in 0..9 {
+            let observed_cell_extender = CaExtender::new(9, 2, 2, cell);
+            assert_eq!(vectorial_function.coordinate_function::<U512Tester>(cell), observed_cell_extender.extend::<U32Tester, U512Tester>(&3755921403));
+        }
+
+        let identity_rule = vec![0xf0f0f0f0u64];
+        let vectorial_function = CaExtender::new(7, 2, 3, 0).extend_vectorial::<GenericTester<5>>(&identity_rule);
+        assert_eq!(vectorial_function.lookup_table(), (0..128).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_reversibility() {
+        let ca_extender = CaExtender::new(9, 2, 2, 4);
+        // Leftmost neighbour rule: the ring map is a rotation
+        let leftmost_neighbour_rule = 0xffff0000u32;
+        assert!(ca_extender.is_reversible::<U32Tester>(&leftmost_neighbour_rule));
+        assert_eq!(ca_extender.ring_map::<U32Tester>(&leftmost_neighbour_rule).evaluate(0b000000001), 0b000000100);
+        assert!(!ca_extender.is_reversible::<U32Tester>(&0));
+        assert!(!ca_extender.is_reversible::<U32Tester>(&3755921403));
+        // Rotation is not reversible with null boundaries, the information of the last cells is lost
+        assert!(!ca_extender.with_boundary_condition(BoundaryCondition::Null).is_reversible::<U32Tester>(&leftmost_neighbour_rule));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hybrid_extension_wrong_schedule_length() {
+        CaExtender::new(9, 2, 2, 4).extend_hybrid_truth_table::<U32Tester>(&RuleSchedule::PerRound(&[]));
+    }
+
+    #[test]
+    fn test_other_sizes() {
+        // The rule returning the center cell keeps the ring unchanged, the extension is then x_observed_cell
+        let identity_rule = vec![0xf0f0f0f0u64];
+        let ca_extender = CaExtender::new(13, 2, 4, 7);
+        let extended_function = ca_extender.extend::<GenericTester<5>, GenericTester<13>>(&identity_rule);
+        assert_eq!(extended_function, GenericTester::<13>::from_truth_table(&(0..1u32 << 13).map(|x| x & (1 << 7) != 0).collect::<Vec<bool>>()));
+
+        let rule_30 = vec![30u64];
+        let extended_function = CaExtender::new(7, 1, 2, 3).extend::<GenericTester<3>, GenericTester<7>>(&rule_30);
+        assert_eq!(GenericTester::<7>::get_function_degree(&extended_function), 3);
+    }
+}
@@ -0,0 +1,275 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use boolean_function_extender::affine_equivalence::EQUIVALENCE_CLASSES;
+use boolean_function_extender::ca_extender::{BoundaryCondition, CaExtender};
+use crate::cli::{boundary_condition_name, parse_boundary_condition, parse_classes, parse_criteria, parse_rule, Criterion, ScanOptions};
+use crate::scan::ScanCounts;
+
+/// State of a scan saved to disk: the options it depends on, the partial counts and the next rule to scan.
+/// The file is made of "key value" lines, followed by one "class index counts..." line per equivalence class.
+/// A finished checkpoint is the result file of its shard, shard_from..=shard_to being the rules it covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    ring_size: usize,
+    iterations: usize,
+    observed_cell: usize,
+    boundary_condition: BoundaryCondition,
+    from: u32,
+    to: u32,
+    shard_from: u32,
+    shard_to: u32,
+    criteria: Vec<Criterion>,
+    classes: Option<Vec<usize>>,
+    enumerate_orbits: bool,
+    cross_check_symmetries: bool,
+    /// None once the whole range has been scanned.
+    pub next_rule: Option<u32>,
+    pub scan_counts: ScanCounts,
+}
+
+impl Checkpoint {
+    /// Checkpoint of a scan that has not processed any rule yet.
+    pub fn new(ca_extender: &CaExtender, scan_options: &ScanOptions) -> Self {
+        let shard_range = scan_options.shard_range();
+        Checkpoint {
+            ring_size: ca_extender.ring_size(),
+            iterations: ca_extender.iterations(),
+            observed_cell: ca_extender.observed_cell(),
+            boundary_condition: ca_extender.boundary_condition(),
+            from: scan_options.from,
+            to: scan_options.to,
+            shard_from: *shard_range.start(),
+            shard_to: *shard_range.end(),
+            criteria: scan_options.criteria.clone(),
+            classes: scan_options.classes.clone(),
+            enumerate_orbits: scan_options.enumerate_orbits,
+            cross_check_symmetries: scan_options.cross_check_symmetries,
+            next_rule: Some(*shard_range.start()),
+            scan_counts: ScanCounts::new(scan_options.criteria.len()),
+        }
+    }
+
+    /// Whether both checkpoints belong to scans with the same options, regardless of their progress.
+    pub fn has_same_scan(&self, other: &Checkpoint) -> bool {
+        Checkpoint {
+            next_rule: other.next_rule,
+            scan_counts: other.scan_counts.clone(),
+            ..self.clone()
+        } == *other
+    }
+
+    /// Whether both checkpoints belong to shards of scans with the same options.
+    fn has_same_sharded_scan(&self, other: &Checkpoint) -> bool {
+        Checkpoint {
+            shard_from: other.shard_from,
+            shard_to: other.shard_to,
+            ..self.clone()
+        }.has_same_scan(other)
+    }
+
+    /// Options of the scan, for printing its counts.
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            from: self.from,
+            to: self.to,
+            criteria: self.criteria.clone(),
+            classes: self.classes.clone(),
+            enumerate_orbits: self.enumerate_orbits,
+            cross_check_symmetries: self.cross_check_symmetries,
+            checkpoint_path: None,
+            checkpoint_interval: 1 << 24,
+            resume: false,
+            shard_index: 0,
+            shard_count: 1,
+            output_path: None,
+        }
+    }
+
+    /// Writes the checkpoint to a temporary file first, so that an interrupted write keeps the previous checkpoint.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let temporary_path = path.with_extension("tmp");
+        fs::write(&temporary_path, self.to_string())
+            .and_then(|_| fs::rename(&temporary_path, path))
+            .map_err(|error| format!("Cannot write checkpoint {}: {}", path.display(), error))
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|error| format!("Cannot read checkpoint {}: {}", path.display(), error))?;
+        Checkpoint::parse(&content).map_err(|error| format!("Invalid checkpoint {}: {}", path.display(), error))
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines();
+        let mut value = |key: &str| -> Result<&str, String> {
+            match lines.next().and_then(|line| line.split_once(' ')) {
+                Some((line_key, value)) if line_key == key => Ok(value),
+                _ => Err(format!("Missing {}", key)),
+            }
+        };
+        let parse_number = |value: &str| value.parse::<usize>().map_err(|_| format!("Invalid number {}", value));
+        let parse_bool = |value: &str| value.parse::<bool>().map_err(|_| format!("Invalid boolean {}", value));
+
+        let ring_size = parse_number(value("ring_size")?)?;
+        let iterations = parse_number(value("iterations")?)?;
+        let observed_cell = parse_number(value("observed_cell")?)?;
+        let boundary_condition = parse_boundary_condition(value("boundary")?)?;
+        let from = parse_rule(value("from")?)?;
+        let to = parse_rule(value("to")?)?;
+        let shard_from = parse_rule(value("shard_from")?)?;
+        let shard_to = parse_rule(value("shard_to")?)?;
+        let criteria = parse_criteria(value("criteria")?)?;
+        let classes = match value("classes")? {
+            "all" => None,
+            classes => Some(parse_classes(classes)?),
+        };
+        let enumerate_orbits = parse_bool(value("orbits")?)?;
+        let cross_check_symmetries = parse_bool(value("cross_check_symmetries")?)?;
+        let next_rule = match value("next")? {
+            "done" => None,
+            next_rule => Some(parse_rule(next_rule)?),
+        };
+        let mut scan_counts = ScanCounts::new(criteria.len());
+        scan_counts.unclassified_count = parse_number(value("unclassified")?)?;
+        scan_counts.symmetry_mismatch_count = parse_number(value("symmetry_mismatches")?)?;
+        for (class_index, counts) in scan_counts.class_counts.iter_mut().enumerate() {
+            let class_counts = value("class")?
+                .split(' ')
+                .map(parse_number)
+                .collect::<Result<Vec<usize>, String>>()?;
+            if class_counts.len() != criteria.len() + 1 || class_counts[0] != class_index {
+                return Err(format!("Invalid counts of class {}", class_index));
+            }
+            counts.copy_from_slice(&class_counts[1..]);
+        }
+
+        Ok(Checkpoint {
+            ring_size,
+            iterations,
+            observed_cell,
+            boundary_condition,
+            from,
+            to,
+            shard_from,
+            shard_to,
+            criteria,
+            classes,
+            enumerate_orbits,
+            cross_check_symmetries,
+            next_rule,
+            scan_counts,
+        })
+    }
+}
+
+/// Sums the result files of the shards of a scan, after checking that they come from the same scan, that they are
+/// finished and that their ranges cover the whole scanned range without overlap.
+pub fn merge_results(result_paths: &[PathBuf]) -> Result<Checkpoint, String> {
+    if result_paths.is_empty() {
+        return Err("No result file to merge".to_string());
+    }
+    let mut results = result_paths.iter()
+        .map(|result_path| Checkpoint::read(result_path).map(|result| (result_path, result)))
+        .collect::<Result<Vec<(&PathBuf, Checkpoint)>, String>>()?;
+    results.sort_by_key(|(_, result)| result.shard_from);
+
+    let (_, first_result) = &results[0];
+    let mut merged_result = Checkpoint {
+        shard_from: first_result.from,
+        shard_to: first_result.to,
+        next_rule: None,
+        scan_counts: ScanCounts::new(first_result.criteria.len()),
+        ..first_result.clone()
+    };
+    let mut next_shard_from = first_result.from as u64;
+    for (result_path, result) in results {
+        if !result.has_same_sharded_scan(&merged_result) {
+            return Err(format!("Result {} was written by a scan with other options", result_path.display()));
+        }
+        if let Some(next_rule) = result.next_rule {
+            return Err(format!("Result {} is not finished, next rule to scan is {:#010x}", result_path.display(), next_rule));
+        }
+        match (result.shard_from as u64).cmp(&next_shard_from) {
+            Ordering::Less => return Err(format!("Result {} overlaps the rules before {:#010x}", result_path.display(), next_shard_from)),
+            Ordering::Greater => return Err(format!("Rules {:#010x} to {:#010x} are not covered", next_shard_from, result.shard_from - 1)),
+            Ordering::Equal => {}
+        }
+        next_shard_from = result.shard_to as u64 + 1;
+        merged_result.scan_counts = merged_result.scan_counts.merge(result.scan_counts);
+    }
+    if next_shard_from <= merged_result.to as u64 {
+        return Err(format!("Rules {:#010x} to {:#010x} are not covered", next_shard_from, merged_result.to));
+    }
+    Ok(merged_result)
+}
+
+impl Display for Checkpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "ring_size {}", self.ring_size)?;
+        writeln!(f, "iterations {}", self.iterations)?;
+        writeln!(f, "observed_cell {}", self.observed_cell)?;
+        writeln!(f, "boundary {}", boundary_condition_name(self.boundary_condition))?;
+        writeln!(f, "from {:#010x}", self.from)?;
+        writeln!(f, "to {:#010x}", self.to)?;
+        writeln!(f, "shard_from {:#010x}", self.shard_from)?;
+        writeln!(f, "shard_to {:#010x}", self.shard_to)?;
+        writeln!(f, "criteria {}", self.criteria.iter().map(|criterion| criterion.name()).collect::<Vec<String>>().join(","))?;
+        match &self.classes {
+            Some(classes) => writeln!(f, "classes {}", classes.iter().map(|class_index| class_index.to_string()).collect::<Vec<String>>().join(","))?,
+            None => writeln!(f, "classes all")?,
+        }
+        writeln!(f, "orbits {}", self.enumerate_orbits)?;
+        writeln!(f, "cross_check_symmetries {}", self.cross_check_symmetries)?;
+        match self.next_rule {
+            Some(next_rule) => writeln!(f, "next {:#010x}", next_rule)?,
+            None => writeln!(f, "next done")?,
+        }
+        writeln!(f, "unclassified {}", self.scan_counts.unclassified_count)?;
+        writeln!(f, "symmetry_mismatches {}", self.scan_counts.symmetry_mismatch_count)?;
+        for class_index in 0..EQUIVALENCE_CLASSES.len() {
+            let counts = self.scan_counts.class_counts[class_index].iter().map(|count| count.to_string()).collect::<Vec<String>>();
+            writeln!(f, "class {} {}", class_index, counts.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boolean_function_extender::ca_extender::{BoundaryCondition, CaExtender};
+    use crate::cli::{Criterion, ScanOptions};
+    use super::Checkpoint;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let scan_options = ScanOptions {
+            from: 0x10,
+            to: 0xffff,
+            criteria: vec![Criterion::StrictAvalanche, Criterion::Propagation(3)],
+            classes: Some(vec![0, 47]),
+            enumerate_orbits: true,
+            cross_check_symmetries: false,
+            checkpoint_path: None,
+            checkpoint_interval: 1 << 24,
+            resume: false,
+            shard_index: 0,
+            shard_count: 1,
+            output_path: None,
+        };
+        let ca_extender = CaExtender::new(9, 2, 2, 4).with_boundary_condition(BoundaryCondition::Fixed(true));
+        let mut checkpoint = Checkpoint::new(&ca_extender, &scan_options);
+        checkpoint.next_rule = Some(0x1000);
+        checkpoint.scan_counts.class_counts[47] = vec![3, 12];
+        checkpoint.scan_counts.unclassified_count = 1;
+        assert_eq!(Checkpoint::parse(&checkpoint.to_string()), Ok(checkpoint.clone()));
+
+        checkpoint.next_rule = None;
+        assert_eq!(Checkpoint::parse(&checkpoint.to_string()), Ok(checkpoint.clone()));
+        assert!(checkpoint.has_same_scan(&Checkpoint::new(&ca_extender, &scan_options)));
+        assert!(!checkpoint.has_same_scan(&Checkpoint::new(&CaExtender::new(9, 2, 2, 4), &scan_options)));
+
+        let truncated_checkpoint = checkpoint.to_string().lines().take(20).collect::<Vec<&str>>().join("\n");
+        assert!(Checkpoint::parse(&truncated_checkpoint).is_err());
+    }
+}
@@ -0,0 +1,372 @@
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use boolean_function_extender::ca_extender::BoundaryCondition;
+
+pub const USAGE: &str = "Usage: boolean_function_extender [OPTIONS] <COMMAND>
+
+Commands:
+    analyze <rule>      Criteria of the rule, of its extension and of its ring map
+    extend <rule>       Truth table of the extension of the rule, in hexadecimal
+    classify <rule>     Affine equivalence class of the rule, with a transformation witness
+    report              Reports on the 48 equivalence class representatives
+    scan                Counts per equivalence class the rules whose extension keeps each criterion
+    merge <file>...     Sums the result files of the shards of a scan, checking they cover the whole range
+
+Extension options, for the analyze, extend, report and scan commands:
+    --ring-size <n>             Number of cells of the ring, between 5 and 16 [default: 9]
+    --iterations <n>            Number of applications of the rule [default: 2]
+    --observed-cell <i>         Cell giving the output of the extension [default: centre of the ring]
+    --boundary <condition>      periodic, null, reflective, fixed-0 or fixed-1 [default: periodic]
+
+Scan options:
+    --from <rule>               First rule of the range [default: 0]
+    --to <rule>                 Last rule of the range, included [default: 0xffffffff]
+    --criteria <list>           Comma separated criteria among sac, ci1, balanced, pc2, pc3, pc4, pc5, degree,
+                                nonlinearity, resiliency, absolute-indicator, sum-of-squares [default: all]
+    --classes <list>            Comma separated indices of the equivalence classes to scan [default: all]
+    --no-orbits                 Extends every rule rather than one rule per symmetry orbit
+    --cross-check-symmetries    Checks the extensions of the other rules of each orbit against the symmetric transforms
+    --checkpoint <file>         Writes the partial counts and the processed range to the file while scanning
+    --checkpoint-interval <n>   Number of rules scanned between two checkpoints [default: 16777216]
+    --resume                    Continues the scan from the checkpoint file, with the same options
+    --shard-index <i>           Index of the part of the range scanned, from 0 [default: 0]
+    --shard-count <n>           Number of parts the range is split into [default: 1]
+    --output <file>             Writes the final counts to a result file, to be merged with the other shards
+
+Rules are given in decimal or in hexadecimal with the 0x prefix.";
+
+/// Criterion kept by the extension, counted by the scan when both the rule and its extension satisfy it,
+/// or when the extension is at least as good as the rule for the comparison criteria.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    StrictAvalanche,
+    FirstOrderCorrelationImmunity,
+    Balanced,
+    Propagation(usize),
+    Degree,
+    Nonlinearity,
+    Resiliency,
+    AbsoluteIndicator,
+    SumOfSquaresIndicator,
+}
+
+impl Criterion {
+    pub const ALL: [Criterion; 12] = [
+        Criterion::StrictAvalanche,
+        Criterion::FirstOrderCorrelationImmunity,
+        Criterion::Balanced,
+        Criterion::Propagation(2),
+        Criterion::Propagation(3),
+        Criterion::Propagation(4),
+        Criterion::Propagation(5),
+        Criterion::Degree,
+        Criterion::Nonlinearity,
+        Criterion::Resiliency,
+        Criterion::AbsoluteIndicator,
+        Criterion::SumOfSquaresIndicator,
+    ];
+
+    /// Name used on the command line.
+    pub fn name(&self) -> String {
+        match self {
+            Criterion::StrictAvalanche => "sac".to_string(),
+            Criterion::FirstOrderCorrelationImmunity => "ci1".to_string(),
+            Criterion::Balanced => "balanced".to_string(),
+            Criterion::Propagation(degree) => format!("pc{}", degree),
+            Criterion::Degree => "degree".to_string(),
+            Criterion::Nonlinearity => "nonlinearity".to_string(),
+            Criterion::Resiliency => "resiliency".to_string(),
+            Criterion::AbsoluteIndicator => "absolute-indicator".to_string(),
+            Criterion::SumOfSquaresIndicator => "sum-of-squares".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Criterion> {
+        Criterion::ALL.into_iter().find(|criterion| criterion.name() == name)
+    }
+}
+
+impl Display for Criterion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Criterion::StrictAvalanche => write!(f, "SAC"),
+            Criterion::FirstOrderCorrelationImmunity => write!(f, "1st order CI"),
+            Criterion::Balanced => write!(f, "Balanced"),
+            Criterion::Propagation(degree) => write!(f, "Prop crit {}", degree),
+            Criterion::Degree => write!(f, "Eq or better deg"),
+            Criterion::Nonlinearity => write!(f, "Eq or better nonlinearity"),
+            Criterion::Resiliency => write!(f, "Eq or better resiliency"),
+            Criterion::AbsoluteIndicator => write!(f, "Eq or better absolute indicator"),
+            Criterion::SumOfSquaresIndicator => write!(f, "Eq or better sum-of-squares indicator"),
+        }
+    }
+}
+
+/// Cellular automaton used to extend the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionOptions {
+    pub ring_size: usize,
+    pub iterations: usize,
+    pub observed_cell: usize,
+    pub boundary_condition: BoundaryCondition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    pub from: u32,
+    pub to: u32,
+    pub criteria: Vec<Criterion>,
+    /// Indices in EQUIVALENCE_CLASSES of the scanned classes, None for all of them.
+    pub classes: Option<Vec<usize>>,
+    pub enumerate_orbits: bool,
+    pub cross_check_symmetries: bool,
+    pub checkpoint_path: Option<PathBuf>,
+    pub checkpoint_interval: u64,
+    pub resume: bool,
+    pub shard_index: u64,
+    pub shard_count: u64,
+    pub output_path: Option<PathBuf>,
+}
+
+impl ScanOptions {
+    /// Rules scanned by the shard, the range being split into shard_count parts of nearly equal sizes.
+    pub fn shard_range(&self) -> RangeInclusive<u32> {
+        let rules_count = self.to as u64 - self.from as u64 + 1;
+        let shard_start = self.from as u64 + rules_count * self.shard_index / self.shard_count;
+        let shard_end = self.from as u64 + rules_count * (self.shard_index + 1) / self.shard_count - 1;
+        shard_start as u32..=shard_end as u32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Analyze(u32),
+    Extend(u32),
+    Classify(u32),
+    Report,
+    Scan(ScanOptions),
+    Merge(Vec<PathBuf>),
+}
+
+/// Parses the command line arguments, without the program name.
+pub fn parse_args(args: &[String]) -> Result<(ExtensionOptions, Command), String> {
+    let mut ring_size = 9;
+    let mut iterations = 2;
+    let mut observed_cell = None;
+    let mut boundary_condition = BoundaryCondition::Periodic;
+    let mut scan_options = ScanOptions {
+        from: 0,
+        to: u32::MAX,
+        criteria: Criterion::ALL.to_vec(),
+        classes: None,
+        enumerate_orbits: true,
+        cross_check_symmetries: false,
+        checkpoint_path: None,
+        checkpoint_interval: 1 << 24,
+        resume: false,
+        shard_index: 0,
+        shard_count: 1,
+        output_path: None,
+    };
+    let mut extension_options_given = false;
+    let mut scan_options_given = false;
+    let mut positionals = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut option_value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--ring-size" => ring_size = parse_number(option_value()?)?,
+            "--iterations" => iterations = parse_number(option_value()?)?,
+            "--observed-cell" => observed_cell = Some(parse_number(option_value()?)?),
+            "--boundary" => boundary_condition = parse_boundary_condition(option_value()?)?,
+            "--from" => scan_options.from = parse_rule(option_value()?)?,
+            "--to" => scan_options.to = parse_rule(option_value()?)?,
+            "--criteria" => scan_options.criteria = parse_criteria(option_value()?)?,
+            "--classes" => scan_options.classes = Some(parse_classes(option_value()?)?),
+            "--no-orbits" => scan_options.enumerate_orbits = false,
+            "--cross-check-symmetries" => scan_options.cross_check_symmetries = true,
+            "--checkpoint" => scan_options.checkpoint_path = Some(PathBuf::from(option_value()?)),
+            "--checkpoint-interval" => scan_options.checkpoint_interval = parse_number(option_value()?)? as u64,
+            "--resume" => scan_options.resume = true,
+            "--shard-index" => scan_options.shard_index = parse_number(option_value()?)? as u64,
+            "--shard-count" => scan_options.shard_count = parse_number(option_value()?)? as u64,
+            "--output" => scan_options.output_path = Some(PathBuf::from(option_value()?)),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+            _ => {
+                positionals.push(arg.as_str());
+                continue;
+            }
+        }
+        extension_options_given |= matches!(arg.as_str(), "--ring-size" | "--iterations" | "--observed-cell" | "--boundary");
+        scan_options_given |= matches!(arg.as_str(), "--from" | "--to" | "--criteria" | "--classes" | "--no-orbits" | "--cross-check-symmetries"
+            | "--checkpoint" | "--checkpoint-interval" | "--resume" | "--shard-index" | "--shard-count" | "--output");
+    }
+
+    if !(5..=16).contains(&ring_size) {
+        return Err("Ring size must be between 5 and 16".to_string());
+    }
+    let observed_cell = observed_cell.unwrap_or((ring_size - 1) / 2);
+    if observed_cell >= ring_size {
+        return Err(format!("Observed cell must be less than the ring size {}", ring_size));
+    }
+    let extension_options = ExtensionOptions {
+        ring_size,
+        iterations,
+        observed_cell,
+        boundary_condition,
+    };
+
+    let command = match positionals.as_slice() {
+        ["analyze", rule] => Command::Analyze(parse_rule(rule)?),
+        ["extend", rule] => Command::Extend(parse_rule(rule)?),
+        ["classify", rule] => Command::Classify(parse_rule(rule)?),
+        ["report"] => Command::Report,
+        ["scan"] => {
+            if scan_options.from > scan_options.to {
+                return Err("Scan range must not be empty".to_string());
+            }
+            if scan_options.checkpoint_interval == 0 {
+                return Err("Checkpoint interval must be positive".to_string());
+            }
+            if scan_options.resume && scan_options.checkpoint_path.is_none() {
+                return Err("Resuming a scan needs a checkpoint file".to_string());
+            }
+            if scan_options.shard_count == 0 || scan_options.shard_count > scan_options.to as u64 - scan_options.from as u64 + 1 {
+                return Err("Shard count must be between 1 and the number of scanned rules".to_string());
+            }
+            if scan_options.shard_index >= scan_options.shard_count {
+                return Err(format!("Shard index must be less than the shard count {}", scan_options.shard_count));
+            }
+            Command::Scan(scan_options)
+        }
+        ["merge", result_paths @ ..] if !result_paths.is_empty() => Command::Merge(result_paths.iter().map(PathBuf::from).collect()),
+        [] => return Err("Missing command".to_string()),
+        _ => return Err(format!("Invalid command {}", positionals.join(" "))),
+    };
+    if scan_options_given && !matches!(command, Command::Scan(_)) {
+        return Err("Scan options are only valid for the scan command".to_string());
+    }
+    if extension_options_given && matches!(command, Command::Classify(_) | Command::Merge(_)) {
+        return Err("Extension options are not valid for the classify and merge commands".to_string());
+    }
+    Ok((extension_options, command))
+}
+
+pub fn parse_rule(value: &str) -> Result<u32, String> {
+    let rule_number = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex_value) => u32::from_str_radix(hex_value, 16),
+        None => value.parse(),
+    };
+    rule_number.map_err(|_| format!("Invalid rule {}", value))
+}
+
+fn parse_number(value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("Invalid number {}", value))
+}
+
+pub fn parse_boundary_condition(value: &str) -> Result<BoundaryCondition, String> {
+    match value {
+        "periodic" => Ok(BoundaryCondition::Periodic),
+        "null" => Ok(BoundaryCondition::Null),
+        "reflective" => Ok(BoundaryCondition::Reflective),
+        "fixed-0" => Ok(BoundaryCondition::Fixed(false)),
+        "fixed-1" => Ok(BoundaryCondition::Fixed(true)),
+        _ => Err(format!("Invalid boundary condition {}", value)),
+    }
+}
+
+/// Name of the boundary condition used on the command line.
+pub fn boundary_condition_name(boundary_condition: BoundaryCondition) -> &'static str {
+    match boundary_condition {
+        BoundaryCondition::Periodic => "periodic",
+        BoundaryCondition::Null => "null",
+        BoundaryCondition::Reflective => "reflective",
+        BoundaryCondition::Fixed(false) => "fixed-0",
+        BoundaryCondition::Fixed(true) => "fixed-1",
+    }
+}
+
+pub fn parse_criteria(value: &str) -> Result<Vec<Criterion>, String> {
+    value.split(',')
+        .map(|name| Criterion::from_name(name).ok_or(format!("Unknown criterion {}", name)))
+        .collect()
+}
+
+pub fn parse_classes(value: &str) -> Result<Vec<usize>, String> {
+    value.split(',')
+        .map(|class_index| match class_index.parse() {
+            Ok(class_index) if class_index < 48 => Ok(class_index),
+            _ => Err(format!("Invalid class index {}, must be less than 48", class_index)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use boolean_function_extender::ca_extender::BoundaryCondition;
+    use super::{parse_args, Command, Criterion, ExtensionOptions};
+
+    fn args(command_line: &str) -> Vec<String> {
+        command_line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let (extension_options, command) = parse_args(&args("analyze 0x8ccdda51")).unwrap();
+        assert_eq!(extension_options, ExtensionOptions {
+            ring_size: 9,
+            iterations: 2,
+            observed_cell: 4,
+            boundary_condition: BoundaryCondition::Periodic,
+        });
+        assert_eq!(command, Command::Analyze(0x8ccdda51));
+        assert_eq!(parse_args(&args("classify 0X8CCDDA51")).unwrap().1, Command::Classify(0x8ccdda51));
+
+        let (extension_options, command) = parse_args(&args("--ring-size 11 --boundary fixed-1 scan --from 16 --to 0x20 --criteria sac,ci1,pc2")).unwrap();
+        assert_eq!((extension_options.ring_size, extension_options.observed_cell), (11, 5));
+        assert_eq!(extension_options.boundary_condition, BoundaryCondition::Fixed(true));
+        match command {
+            Command::Scan(scan_options) => {
+                assert_eq!((scan_options.from, scan_options.to), (16, 32));
+                assert_eq!(scan_options.criteria, vec![Criterion::StrictAvalanche, Criterion::FirstOrderCorrelationImmunity, Criterion::Propagation(2)]);
+                assert!(scan_options.enumerate_orbits);
+            }
+            _ => panic!("Expected a scan command"),
+        }
+    }
+
+    #[test]
+    fn test_shard_range() {
+        let shard_ranges = (0..3)
+            .map(|shard_index| match parse_args(&args(&format!("scan --from 10 --to 20 --shard-index {} --shard-count 3", shard_index))).unwrap().1 {
+                Command::Scan(scan_options) => scan_options.shard_range(),
+                _ => panic!("Expected a scan command"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(shard_ranges, vec![10..=12, 13..=16, 17..=20]);
+        match parse_args(&args("scan --shard-index 1 --shard-count 2")).unwrap().1 {
+            Command::Scan(scan_options) => assert_eq!(scan_options.shard_range(), 0x80000000..=0xffffffff),
+            _ => panic!("Expected a scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args("")).is_err());
+        assert!(parse_args(&args("analyze")).is_err());
+        assert!(parse_args(&args("extend 0xzz")).is_err());
+        assert!(parse_args(&args("scan --criteria sac,foo")).is_err());
+        assert!(parse_args(&args("scan --from 10 --to 9")).is_err());
+        assert!(parse_args(&args("classify 30 --from 10")).is_err());
+        assert!(parse_args(&args("--ring-size 4 report")).is_err());
+        assert!(parse_args(&args("--observed-cell 9 report")).is_err());
+        assert!(parse_args(&args("scan --resume")).is_err());
+        assert!(parse_args(&args("scan --shard-index 3 --shard-count 3")).is_err());
+        assert!(parse_args(&args("scan --from 0 --to 1 --shard-count 3")).is_err());
+        assert!(parse_args(&args("merge")).is_err());
+        assert!(parse_args(&args("--ring-size 11 merge shard_0.txt shard_1.txt")).is_err());
+        assert!(parse_args(&args("classify 30 --boundary null")).is_err());
+    }
+}
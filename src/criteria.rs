@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+use crate::BooleanFunctionTester;
+
+/// Summary of the cryptographic criteria of a Boolean function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptographicCriteria {
+    pub balanced: bool,
+    pub degree: usize,
+    pub nonlinearity: u32,
+    pub correlation_immunity_order: usize,
+    pub resiliency_order: Option<usize>,
+    pub strict_avalanche_criterion: bool,
+    /// Largest k for which the propagation criterion of degree k holds.
+    pub propagation_criterion_degree: usize,
+    pub algebraic_immunity: usize,
+    pub absolute_indicator: u32,
+    pub sum_of_squares_indicator: u64,
+}
+
+impl CryptographicCriteria {
+    pub fn compute<T: BooleanFunctionTester>(rule_number: &T::UnsignedRepr) -> Self {
+        CryptographicCriteria {
+            balanced: T::is_function_balanced(rule_number),
+            degree: T::get_function_degree(rule_number),
+            nonlinearity: T::nonlinearity(rule_number),
+            correlation_immunity_order: T::correlation_immunity_order(rule_number),
+            resiliency_order: T::resiliency_order(rule_number),
+            strict_avalanche_criterion: T::is_strict_avalanche_criterion_ok(rule_number),
+            propagation_criterion_degree: (1..=T::NUM_VARIABLES)
+                .take_while(|&k| T::is_propagation_criterion_deg_k_ok(rule_number, k))
+                .count(),
+            algebraic_immunity: T::algebraic_immunity(rule_number).0,
+            absolute_indicator: T::absolute_indicator(rule_number),
+            sum_of_squares_indicator: T::sum_of_squares_indicator(rule_number),
+        }
+    }
+}
+
+impl Display for CryptographicCriteria {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "balanced: {}, degree: {}, nonlinearity: {}, CI order: {}, resiliency: ", self.balanced, self.degree, self.nonlinearity, self.correlation_immunity_order)?;
+        match self.resiliency_order {
+            Some(resiliency_order) => write!(f, "{}", resiliency_order)?,
+            None => write!(f, "-")?,
+        }
+        write!(f, ", SAC: {}, PC degree: {}, AI: {}, absolute indicator: {}, sum-of-squares indicator: {}",
+               self.strict_avalanche_criterion, self.propagation_criterion_degree, self.algebraic_immunity, self.absolute_indicator, self.sum_of_squares_indicator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::u32_tester::U32Tester;
+    use super::CryptographicCriteria;
+
+    #[test]
+    fn test_compute() {
+        let criteria = CryptographicCriteria::compute::<U32Tester>(&0x96696996);
+        assert_eq!(criteria, CryptographicCriteria {
+            balanced: true,
+            degree: 1,
+            nonlinearity: 0,
+            correlation_immunity_order: 4,
+            resiliency_order: Some(4),
+            strict_avalanche_criterion: false,
+            propagation_criterion_degree: 0,
+            algebraic_immunity: 1,
+            absolute_indicator: 32,
+            sum_of_squares_indicator: 32768,
+        });
+        assert_eq!(criteria.to_string(), "balanced: true, degree: 1, nonlinearity: 0, CI order: 4, resiliency: 4, SAC: false, PC degree: 0, AI: 1, absolute indicator: 32, sum-of-squares indicator: 32768");
+        assert!(CryptographicCriteria::compute::<U32Tester>(&0).to_string().contains("resiliency: -"));
+    }
+}
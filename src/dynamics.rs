@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use crate::ca_extender::CaExtender;
+use crate::u32_tester::U32Tester;
+use crate::vectorial::VectorialFunction;
+
+/// State-transition graph of the iterated global ring map, each ring state having exactly one successor.
+#[derive(Debug, Clone)]
+pub struct StateTransitionGraph {
+    successors: Vec<u32>,
+    /// Number of steps before reaching a cycle, 0 for states lying on a cycle.
+    transient_lengths: Vec<usize>,
+    /// Length of the cycle eventually reached from each state.
+    reached_cycle_lengths: Vec<usize>,
+    cycle_lengths: Vec<usize>,
+}
+
+impl StateTransitionGraph {
+    /// State-transition graph of the map applying the 5-variable rule once to every cell of a periodic ring.
+    pub fn from_rule(rule_number: u32, ring_size: usize) -> Self {
+        Self::from_ca_extender(&CaExtender::new(ring_size, 2, 1, 0), rule_number)
+    }
+
+    /// State-transition graph of the one-step ring map of the extender, over its ring size and boundary condition.
+    /// Each ring state is a node, whatever the iterations and observed cell of the extender.
+    pub fn from_ca_extender(ca_extender: &CaExtender, rule_number: u32) -> Self {
+        Self::from_map(&ca_extender.ring_map::<U32Tester>(&rule_number))
+    }
+
+    pub fn from_map(map: &VectorialFunction) -> Self {
+        if map.num_inputs() != map.num_outputs() {
+            panic!("Map must have as many inputs as outputs");
+        }
+        let successors = map.lookup_table().to_vec();
+        let states_count = successors.len();
+        const UNVISITED: usize = usize::MAX;
+        let mut transient_lengths = vec![UNVISITED; states_count];
+        let mut reached_cycle_lengths = vec![0usize; states_count];
+        let mut cycle_lengths = Vec::new();
+        // Position in the current path, to detect when the path closes on itself
+        let mut path_positions = vec![UNVISITED; states_count];
+
+        for start_state in 0..states_count {
+            if transient_lengths[start_state] != UNVISITED {
+                continue;
+            }
+            let mut path = Vec::new();
+            let mut state = start_state;
+            while transient_lengths[state] == UNVISITED && path_positions[state] == UNVISITED {
+                path_positions[state] = path.len();
+                path.push(state);
+                state = successors[state] as usize;
+            }
+            let tail_length = if path_positions[state] != UNVISITED {
+                // New cycle found, made of the end of the path starting at state
+                let cycle_start = path_positions[state];
+                let cycle_length = path.len() - cycle_start;
+                cycle_lengths.push(cycle_length);
+                for &cycle_state in &path[cycle_start..] {
+                    transient_lengths[cycle_state] = 0;
+                    reached_cycle_lengths[cycle_state] = cycle_length;
+                }
+                cycle_start
+            } else {
+                path.len()
+            };
+            // The remaining path states lead to an already analyzed state
+            let (mut transient_length, cycle_length) = (transient_lengths[state], reached_cycle_lengths[state]);
+            for &tail_state in path[..tail_length].iter().rev() {
+                transient_length += 1;
+                transient_lengths[tail_state] = transient_length;
+                reached_cycle_lengths[tail_state] = cycle_length;
+            }
+            for &path_state in &path {
+                path_positions[path_state] = UNVISITED;
+            }
+        }
+
+        StateTransitionGraph {
+            successors,
+            transient_lengths,
+            reached_cycle_lengths,
+            cycle_lengths,
+        }
+    }
+
+    pub fn successor(&self, state: u32) -> u32 {
+        self.successors[state as usize]
+    }
+
+    /// Lengths of all the cycles of the graph, in discovery order.
+    pub fn cycle_lengths(&self) -> &[usize] {
+        &self.cycle_lengths
+    }
+
+    pub fn cycles_count(&self) -> usize {
+        self.cycle_lengths.len()
+    }
+
+    /// Number of cycles of each length.
+    pub fn cycle_length_distribution(&self) -> HashMap<usize, usize> {
+        let mut cycle_length_count_map: HashMap<usize, usize> = HashMap::new();
+        self.cycle_lengths.iter().for_each(|&cycle_length| {
+            *cycle_length_count_map.entry(cycle_length).or_insert(0) += 1;
+        });
+        cycle_length_count_map
+    }
+
+    /// Number of iterations from the state before reaching a cycle.
+    pub fn transient_length(&self, state: u32) -> usize {
+        self.transient_lengths[state as usize]
+    }
+
+    pub fn max_transient_length(&self) -> usize {
+        self.transient_lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Number of states having each transient length.
+    pub fn transient_length_distribution(&self) -> HashMap<usize, usize> {
+        let mut transient_length_count_map: HashMap<usize, usize> = HashMap::new();
+        self.transient_lengths.iter().for_each(|&transient_length| {
+            *transient_length_count_map.entry(transient_length).or_insert(0) += 1;
+        });
+        transient_length_count_map
+    }
+
+    /// Length of the cycle eventually reached from the state.
+    pub fn reached_cycle_length(&self, state: u32) -> usize {
+        self.reached_cycle_lengths[state as usize]
+    }
+
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut in_degrees = vec![0usize; self.successors.len()];
+        self.successors.iter().for_each(|&successor| in_degrees[successor as usize] += 1);
+        in_degrees
+    }
+
+    /// Number of states having each in-degree.
+    pub fn in_degree_distribution(&self) -> HashMap<usize, usize> {
+        let mut in_degree_count_map: HashMap<usize, usize> = HashMap::new();
+        self.in_degrees().iter().for_each(|&in_degree| {
+            *in_degree_count_map.entry(in_degree).or_insert(0) += 1;
+        });
+        in_degree_count_map
+    }
+
+    /// States without any predecessor.
+    pub fn garden_of_eden_states(&self) -> Vec<u32> {
+        self.in_degrees()
+            .iter()
+            .enumerate()
+            .filter(|(_, &in_degree)| in_degree == 0)
+            .map(|(state, _)| state as u32)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::vectorial::VectorialFunction;
+    use super::StateTransitionGraph;
+
+    #[test]
+    fn test_from_map() {
+        // 0 -> 1 -> 2 -> 3 -> 1, 4 -> 2, 5 -> 5, 6 -> 7 -> 6
+        let state_transition_graph = StateTransitionGraph::from_map(&VectorialFunction::new(3, 3, vec![1, 2, 3, 1, 2, 5, 7, 6]));
+        assert_eq!(state_transition_graph.cycles_count(), 3);
+        assert_eq!(state_transition_graph.cycle_length_distribution(), HashMap::from([(3, 1), (1, 1), (2, 1)]));
+        assert_eq!((0..8).map(|state| state_transition_graph.transient_length(state)).collect::<Vec<usize>>(), vec![1, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(state_transition_graph.max_transient_length(), 1);
+        assert_eq!(state_transition_graph.reached_cycle_length(4), 3);
+        assert_eq!(state_transition_graph.reached_cycle_length(6), 2);
+        assert_eq!(state_transition_graph.garden_of_eden_states(), vec![0, 4]);
+        assert_eq!(state_transition_graph.in_degree_distribution(), HashMap::from([(0, 2), (1, 4), (2, 2)]));
+        assert_eq!(state_transition_graph.successor(3), 1);
+    }
+
+    #[test]
+    fn test_from_rule() {
+        // Leftmost neighbour rule: the ring map is a rotation by 2 cells, of order 9 on a ring of 9 cells
+        let state_transition_graph = StateTransitionGraph::from_rule(0xffff0000, 9);
+        assert!(state_transition_graph.garden_of_eden_states().is_empty());
+        assert_eq!(state_transition_graph.max_transient_length(), 0);
+        // Fixed points 000000000 and 111111111, the other 510 states lie on cycles of length 9 (3 for 001001001 and 011011011)
+        assert_eq!(state_transition_graph.cycle_length_distribution(), HashMap::from([(1, 2), (3, 2), (9, 56)]));
+
+        // Constant 0 rule: every state goes to 0 in one step
+        let state_transition_graph = StateTransitionGraph::from_rule(0, 9);
+        assert_eq!(state_transition_graph.cycle_lengths(), &[1]);
+        assert_eq!(state_transition_graph.garden_of_eden_states().len(), 511);
+        assert_eq!(state_transition_graph.transient_length_distribution(), HashMap::from([(0, 1), (1, 511)]));
+    }
+}
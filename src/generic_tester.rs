@@ -0,0 +1,173 @@
+use crate::BooleanFunctionTester;
+
+/// Tester for Boolean functions of any number of variables N from 1 to 16, the truth table being stored
+/// as little-endian 64 bits words (bit x of the truth table is bit x % 64 of word x / 64).
+pub struct GenericTester<const N: usize>;
+
+impl<const N: usize> BooleanFunctionTester for GenericTester<N> {
+    type UnsignedRepr = Vec<u64>;
+    const NUM_VARIABLES: usize = {
+        assert!(N >= 1 && N <= 16, "GenericTester supports from 1 to 16 variables");
+        N
+    };
+    const MAX_INPUT_VALUE: u32 = 2u32.pow(Self::NUM_VARIABLES as u32) - 1;
+
+    fn max_function_number() -> Self::UnsignedRepr {
+        let mut max_function_number = vec![u64::MAX; Self::words_count()];
+        if Self::NUM_VARIABLES < 6 {
+            max_function_number[0] = (1 << (1 << Self::NUM_VARIABLES)) - 1;
+        }
+        max_function_number
+    }
+
+    fn from_truth_table(truth_table: &[bool]) -> Self::UnsignedRepr {
+        let mut rule_number = vec![0u64; Self::words_count()];
+        truth_table.iter()
+            .enumerate()
+            .filter(|(_, &value)| value)
+            .for_each(|(x, _)| rule_number[x / 64] |= 1 << (x % 64));
+        rule_number
+    }
+
+    fn fast_bool_anf_transform_unsigned(rule_number: &Self::UnsignedRepr, num_variables_function: usize) -> Self::UnsignedRepr {
+        // For each variable i, the positions with bit i unset are XORed into the positions with bit i set
+        const INTRA_WORD_MASKS: [u64; 6] = [0x5555555555555555, 0x3333333333333333, 0x0f0f0f0f0f0f0f0f, 0x00ff00ff00ff00ff, 0x0000ffff0000ffff, 0x00000000ffffffff];
+
+        let mut final_f = rule_number.clone();
+        for (i, &mask) in INTRA_WORD_MASKS.iter().enumerate().take(num_variables_function) {
+            final_f.iter_mut().for_each(|word| *word ^= (*word & mask) << (1 << i));
+        }
+        for i in 6..num_variables_function {
+            let words_blocksize = 1 << (i - 6);
+            for target in (0..final_f.len()).filter(|target| target & words_blocksize != 0) {
+                final_f[target] ^= final_f[target ^ words_blocksize];
+            }
+        }
+        final_f
+    }
+
+    fn get_function_degree(rule_number: &Self::UnsignedRepr) -> usize {
+        let anf_form = Self::fast_bool_anf_transform_unsigned(rule_number, Self::NUM_VARIABLES);
+        (0..=Self::MAX_INPUT_VALUE).map(|bit_position| {
+            if Self::compute_cellular_automata_rule(&anf_form, bit_position) {
+                bit_position.count_ones() as usize
+            } else {
+                0
+            }
+        }).max().unwrap_or(0)
+    }
+
+    fn compute_cellular_automata_rule(rule_number: &Self::UnsignedRepr, input_bits: u32) -> bool {
+        #[cfg(debug_assertions)]
+        if input_bits > Self::MAX_INPUT_VALUE {
+            panic!("Input bits must be less or equal than {}", Self::MAX_INPUT_VALUE);
+        }
+        (rule_number[input_bits as usize / 64] >> (input_bits % 64)) & 1 != 0
+    }
+
+    fn is_function_balanced(rule_number: &Self::UnsignedRepr) -> bool {
+        let expected_set_number: u32 = 1 << (Self::NUM_VARIABLES - 1);
+        rule_number.iter().map(|word| word.count_ones()).sum::<u32>() == expected_set_number
+    }
+
+    fn is_function_linear(rule_number: &Self::UnsignedRepr) -> bool {
+        Self::get_function_degree(rule_number) <= 1
+    }
+}
+
+impl<const N: usize> GenericTester<N> {
+    fn words_count() -> usize {
+        (1usize << Self::NUM_VARIABLES).div_ceil(64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use numext_fixed_uint::U512;
+    use crate::BooleanFunctionTester;
+    use crate::u32_tester::U32Tester;
+    use crate::u512_tester::U512Tester;
+    use super::GenericTester;
+
+    fn u512_to_words(rule_number: &U512) -> Vec<u64> {
+        (0..8).map(|word_index| {
+            (0..64).filter(|bit| rule_number.bit(word_index * 64 + bit).unwrap()).fold(0u64, |word, bit| word | (1 << bit))
+        }).collect()
+    }
+
+    #[test]
+    fn test_matches_u32_tester() {
+        for rule_number in [0u32, 3755921403, 3755921407, 0x96696996, 0x78787878, 0xfee8e880, u32::MAX] {
+            let words = vec![rule_number as u64];
+            assert_eq!(GenericTester::<5>::fast_bool_anf_transform_unsigned(&words, 5), vec![U32Tester::fast_bool_anf_transform_unsigned(&rule_number, 5) as u64]);
+            assert_eq!(GenericTester::<5>::get_function_degree(&words), U32Tester::get_function_degree(&rule_number));
+            assert_eq!(GenericTester::<5>::walsh_spectrum(&words), U32Tester::walsh_spectrum(&rule_number));
+            assert_eq!(GenericTester::<5>::autocorrelation_spectrum(&words), U32Tester::autocorrelation_spectrum(&rule_number));
+            assert_eq!(GenericTester::<5>::is_function_balanced(&words), U32Tester::is_function_balanced(&rule_number));
+            assert_eq!(GenericTester::<5>::is_function_linear(&words), U32Tester::is_function_linear(&rule_number));
+            for k in 0..=5 {
+                assert_eq!(GenericTester::<5>::is_propagation_criterion_deg_k_ok(&words, k), U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, k));
+            }
+        }
+        assert_eq!(GenericTester::<5>::max_function_number(), vec![u32::MAX as u64]);
+    }
+
+    #[test]
+    fn test_matches_u512_tester() {
+        let majority_function = (0..512u32)
+            .filter(|x| x.count_ones() >= 5)
+            .fold(U512::zero(), |function, x| function | (U512::one() << x));
+        for rule_number in [U512::zero(), majority_function, U512::from_dec_str("13407807929942597098847186317233203207567774193617110470605594638121991820277051203989568509643346170367187143529348261601759818256683210860310861684080895").unwrap()] {
+            let words = u512_to_words(&rule_number);
+            assert_eq!(GenericTester::<9>::fast_bool_anf_transform_unsigned(&words, 9), u512_to_words(&U512Tester::fast_bool_anf_transform_unsigned(&rule_number, 9)));
+            assert_eq!(GenericTester::<9>::get_function_degree(&words), U512Tester::get_function_degree(&rule_number));
+            assert_eq!(GenericTester::<9>::walsh_spectrum(&words), U512Tester::walsh_spectrum(&rule_number));
+            assert_eq!(GenericTester::<9>::is_strict_avalanche_criterion_ok(&words), U512Tester::is_strict_avalanche_criterion_ok(&rule_number));
+        }
+    }
+
+    #[test]
+    fn test_other_sizes() {
+        // x0 x1 + x2
+        let rule_number = GenericTester::<3>::from_truth_table(&[false, false, false, true, true, true, true, false]);
+        assert_eq!(rule_number, vec![0x78]);
+        assert_eq!(GenericTester::<3>::get_function_degree(&rule_number), 2);
+        assert_eq!(GenericTester::<3>::max_function_number(), vec![0xff]);
+        assert_eq!(GenericTester::<1>::max_function_number(), vec![0b11]);
+
+        let parity_function = GenericTester::<13>::from_truth_table(&(0..1u32 << 13).map(|x| x.count_ones() % 2 == 1).collect::<Vec<bool>>());
+        assert_eq!(parity_function.len(), 128);
+        assert!(GenericTester::<13>::is_function_linear(&parity_function));
+        assert!(GenericTester::<13>::is_function_balanced(&parity_function));
+        assert_eq!(GenericTester::<13>::resiliency_order(&parity_function), Some(12));
+
+        let bent_function = GenericTester::<16>::from_truth_table(&(0..1u32 << 16).map(|x| (x & (x >> 8)).count_ones() % 2 == 1).collect::<Vec<bool>>());
+        assert!(GenericTester::<16>::is_bent(&bent_function));
+        assert_eq!(GenericTester::<16>::get_function_degree(&bent_function), 2);
+        assert_eq!(GenericTester::<16>::nonlinearity(&bent_function), (1 << 15) - (1 << 7));
+    }
+
+    #[test]
+    fn test_six_variables() {
+        assert_eq!(GenericTester::<6>::get_function_degree(&vec![0]), 0);
+        assert_eq!(GenericTester::<6>::get_function_degree(&vec![0x8777788878887888]), 2);
+        assert_eq!(GenericTester::<6>::get_function_degree(&vec![u64::MAX]), 0);
+        assert_eq!(GenericTester::<6>::absolute_walsh_spectrum(&vec![0]), HashMap::from([(64, 1), (0, 63)]));
+        assert_eq!(GenericTester::<6>::absolute_walsh_spectrum(&vec![0x8777788878887888]), HashMap::from([(8, 64)]));
+        assert_eq!(GenericTester::<6>::absolute_walsh_spectrum(&vec![0x7888788878887888]), HashMap::from([(16, 16), (0, 48)]));
+    }
+
+    #[test]
+    fn test_bent_and_plateaued() {
+        assert!(GenericTester::<6>::is_bent(&vec![0x8777788878887888]));
+        assert!(!GenericTester::<6>::is_semi_bent(&vec![0x8777788878887888]));
+        assert_eq!(GenericTester::<6>::plateau_amplitude(&vec![0x8777788878887888]), Some(8));
+        assert!(!GenericTester::<6>::is_bent(&vec![0x7888788878887888]));
+        assert!(GenericTester::<6>::is_semi_bent(&vec![0x7888788878887888]));
+        assert_eq!(GenericTester::<6>::plateau_amplitude(&vec![0x7888788878887888]), Some(16));
+        assert!(GenericTester::<6>::is_plateaued(&vec![0]));
+        assert!(!GenericTester::<6>::is_plateaued(&vec![0xfee8e880e8808000]));
+        assert_eq!(GenericTester::<6>::plateau_amplitude(&vec![0xfee8e880e8808000]), None);
+    }
+}
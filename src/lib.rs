@@ -1,8 +1,20 @@
 use std::arch::x86_64::_popcnt32;
 use std::collections::HashMap;
+use std::ops::{Add, Sub};
+use gen_combinations::CombinationIterator;
 
 pub mod u512_tester;
 pub mod u32_tester;
+pub mod generic_tester;
+pub mod ca_extender;
+pub mod criteria;
+pub mod vectorial;
+pub mod reversibility;
+pub mod dynamics;
+pub mod affine_equivalence;
+pub mod orbits;
+pub mod symmetry;
+mod algebraic_immunity;
 
 pub trait BooleanFunctionTester {
     type UnsignedRepr;
@@ -10,32 +22,238 @@ pub trait BooleanFunctionTester {
     const NUM_VARIABLES: usize;
     const MAX_INPUT_VALUE: u32;
 
-    const MAX_FUNCTION_NUMBER: Self::UnsignedRepr;
+    fn max_function_number() -> Self::UnsignedRepr;
+
+    fn from_truth_table(truth_table: &[bool]) -> Self::UnsignedRepr;
 
     fn fast_bool_anf_transform_unsigned(rule_number: &Self::UnsignedRepr, num_variables_function: usize) -> Self::UnsignedRepr;
 
     fn get_function_degree(rule_number: &Self::UnsignedRepr) -> usize;
 
-    fn is_strict_avalanche_criterion_ok(rule_number: &Self::UnsignedRepr) -> bool;
-
     fn compute_cellular_automata_rule(rule_number: &Self::UnsignedRepr, input_bits: u32) -> bool;
 
     fn is_function_balanced(rule_number: &Self::UnsignedRepr) -> bool;
 
-    fn is_first_order_correlation_immune(rule_number: &Self::UnsignedRepr) -> bool;
+    fn is_first_order_correlation_immune(rule_number: &Self::UnsignedRepr) -> bool {
+        let walsh_spectrum = Self::walsh_spectrum(rule_number);
+        (0..Self::NUM_VARIABLES)
+            .map(|input_bit_number| {
+                1 << input_bit_number
+            })
+            .all(|w: usize| {
+                walsh_spectrum[w] == 0
+            })
+    }
+
+    /// Full Walsh spectrum, indexed by w, computed with the in-place butterfly in O(n.2^n).
+    fn walsh_spectrum(rule_number: &Self::UnsignedRepr) -> Vec<i32> {
+        let mut walsh_spectrum = (0..=Self::MAX_INPUT_VALUE)
+            .map(|x| {
+                if Self::compute_cellular_automata_rule(rule_number, x) {
+                    -1
+                } else {
+                    1
+                }
+            })
+            .collect::<Vec<i32>>();
+        walsh_hadamard_butterfly(&mut walsh_spectrum);
+        walsh_spectrum
+    }
+
+    // https://doc.sagemath.org/html/en/reference/cryptography/sage/crypto/boolean_function.html#sage.crypto.boolean_function.BooleanFunction.walsh_hadamard_transform
+    fn fast_walsh_transform(rule_number: &Self::UnsignedRepr, w: u32) -> i32 {
+        Self::walsh_spectrum(rule_number)[w as usize]
+    }
+
+    fn absolute_walsh_spectrum(rule_number: &Self::UnsignedRepr) -> HashMap<u32, usize> {
+        let mut absolute_walsh_value_count_map: HashMap<u32, usize> = HashMap::new();
+        Self::walsh_spectrum(rule_number)
+            .iter()
+            .for_each(|walsh_value| {
+                *absolute_walsh_value_count_map.entry(walsh_value.unsigned_abs()).or_insert(0) += 1;
+            });
+        absolute_walsh_value_count_map
+    }
+
+    /// Common absolute value of the nonzero Walsh coefficients, if the function is plateaued.
+    fn plateau_amplitude(rule_number: &Self::UnsignedRepr) -> Option<u32> {
+        let absolute_walsh_spectrum = Self::absolute_walsh_spectrum(rule_number);
+        let mut nonzero_absolute_walsh_values = absolute_walsh_spectrum.keys().filter(|&&absolute_walsh_value| absolute_walsh_value != 0);
+        match (nonzero_absolute_walsh_values.next(), nonzero_absolute_walsh_values.next()) {
+            (Some(&amplitude), None) => Some(amplitude),
+            _ => None,
+        }
+    }
+
+    fn is_plateaued(rule_number: &Self::UnsignedRepr) -> bool {
+        Self::plateau_amplitude(rule_number).is_some()
+    }
+
+    /// Bent functions only exist for an even number of variables, all their Walsh coefficients are ±2^(n/2).
+    fn is_bent(rule_number: &Self::UnsignedRepr) -> bool {
+        Self::NUM_VARIABLES % 2 == 0 && Self::walsh_spectrum(rule_number)
+            .iter()
+            .all(|walsh_value| walsh_value.unsigned_abs() == 1 << (Self::NUM_VARIABLES / 2))
+    }
+
+    /// Walsh coefficients in {0, ±2^((n+2)/2)} for even n, {0, ±2^((n+1)/2)} for odd n (near-bent).
+    fn is_semi_bent(rule_number: &Self::UnsignedRepr) -> bool {
+        Self::plateau_amplitude(rule_number) == Some(1 << (Self::NUM_VARIABLES / 2 + 1))
+    }
+
+    /// Full autocorrelation spectrum, indexed by w, computed from the Walsh spectrum with the
+    /// Wiener-Khinchin relation r_f(w) = 2^-n sum_u W_f(u)^2 (-1)^(u.w).
+    fn autocorrelation_spectrum(rule_number: &Self::UnsignedRepr) -> Vec<i32> {
+        let mut autocorrelation_spectrum = Self::walsh_spectrum(rule_number)
+            .iter()
+            .map(|&walsh_value| (walsh_value as i64) * (walsh_value as i64))
+            .collect::<Vec<i64>>();
+        walsh_hadamard_butterfly(&mut autocorrelation_spectrum);
+        autocorrelation_spectrum
+            .iter()
+            .map(|&squared_walsh_sum| (squared_walsh_sum >> Self::NUM_VARIABLES) as i32)
+            .collect()
+    }
+
+    fn fast_auto_correlation_transform(rule_number: &Self::UnsignedRepr, w: u32) -> i32 {
+        Self::autocorrelation_spectrum(rule_number)[w as usize]
+    }
+
+    fn absolute_autocorrelation_spectrum(rule_number: &Self::UnsignedRepr) -> HashMap<u32, usize> {
+        let mut absolute_autocorrelation_value_count_map: HashMap<u32, usize> = HashMap::new();
+        Self::autocorrelation_spectrum(rule_number)
+            .iter()
+            .for_each(|autocorrelation_value| {
+                *absolute_autocorrelation_value_count_map.entry(autocorrelation_value.unsigned_abs()).or_insert(0) += 1;
+            });
+        absolute_autocorrelation_value_count_map
+    }
+
+    /// Global avalanche characteristic: max over nonzero a of |r_f(a)|.
+    fn absolute_indicator(rule_number: &Self::UnsignedRepr) -> u32 {
+        Self::autocorrelation_spectrum(rule_number)
+            .iter()
+            .skip(1)
+            .map(|autocorrelation_value| autocorrelation_value.unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Global avalanche characteristic: sum over all a of r_f(a)^2.
+    fn sum_of_squares_indicator(rule_number: &Self::UnsignedRepr) -> u64 {
+        Self::autocorrelation_spectrum(rule_number)
+            .iter()
+            .map(|&autocorrelation_value| (autocorrelation_value as i64 * autocorrelation_value as i64) as u64)
+            .sum()
+    }
+
+    /// Nonzero vectors a for which the derivative D_a f(x) = f(x) + f(x + a) is constant, along with that
+    /// constant value (r_f(a) = 2^n when D_a f = 0, r_f(a) = -2^n when D_a f = 1).
+    fn linear_structures(rule_number: &Self::UnsignedRepr) -> Vec<(u32, bool)> {
+        Self::autocorrelation_spectrum(rule_number)
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, autocorrelation_value)| autocorrelation_value.unsigned_abs() == 1 << Self::NUM_VARIABLES)
+            .map(|(a, &autocorrelation_value)| (a as u32, autocorrelation_value < 0))
+            .collect()
+    }
 
-    fn fast_walsh_transform(rule_number: &Self::UnsignedRepr, w: u32) -> i32;
+    fn correlation_immunity_order(rule_number: &Self::UnsignedRepr) -> usize {
+        let walsh_spectrum = Self::walsh_spectrum(rule_number);
+        (1..=Self::NUM_VARIABLES)
+            .take_while(|&order| {
+                walsh_spectrum.iter()
+                    .enumerate()
+                    .filter(|(w, _)| w.count_ones() as usize == order)
+                    .all(|(_, &walsh_value)| {
+                        walsh_value == 0
+                    })
+            })
+            .count()
+    }
 
-    fn absolute_walsh_spectrum(rule_number: &Self::UnsignedRepr) -> HashMap<u32, usize>;
+    fn resiliency_order(rule_number: &Self::UnsignedRepr) -> Option<usize> {
+        if !Self::is_function_balanced(rule_number) {
+            return None;
+        }
+        Some(Self::correlation_immunity_order(rule_number))
+    }
 
-    fn is_propagation_criterion_deg_k_ok(rule_number: &Self::UnsignedRepr, k: usize) -> bool;
+    fn is_strict_avalanche_criterion_ok(rule_number: &Self::UnsignedRepr) -> bool {
+        (0..Self::NUM_VARIABLES).all(|constant_position| {
+            let constant = 1 << constant_position;
+            (0..=Self::MAX_INPUT_VALUE).filter(|&x| {
+                let x_prime = x ^ constant;
+                Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
+            }).count() == (1 << (Self::NUM_VARIABLES - 1))
+        })
+    }
+
+    fn is_propagation_criterion_deg_k_ok(rule_number: &Self::UnsignedRepr, k: usize) -> bool {
+        if k == 0 {
+            return true;
+        }
+        let strict_avalanche_criterion_ok = Self::is_strict_avalanche_criterion_ok(rule_number);
+        if k == 1 {
+            return strict_avalanche_criterion_ok;
+        }
+        if k >= 1 && !strict_avalanche_criterion_ok {
+            return false;
+        }
+        let possible_reversable_bit_position = (0..Self::NUM_VARIABLES).collect::<Vec<usize>>();
+        (2..=k).all(|criterion_degree| {
+            CombinationIterator::new(&possible_reversable_bit_position, criterion_degree)
+                .all(|combination| {
+                    let mut bit_mask = 0;
+                    for &bit_position in combination {
+                        bit_mask |= (1 << bit_position) as u32;
+                    }
+                    let function_equal_mask_count = (0..=Self::MAX_INPUT_VALUE).filter(|&x| {
+                        let x_prime = x ^ bit_mask;
+                        Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
+                    }).count();
+                    function_equal_mask_count == (1 << (Self::NUM_VARIABLES - 1))
+                })
+        })
+    }
 
     fn is_function_linear(rule_number: &Self::UnsignedRepr) -> bool;
 
+    // https://doc.sagemath.org/html/en/reference/cryptography/sage/crypto/boolean_function.html#sage.crypto.boolean_function.BooleanFunction.nonlinearity
+    fn nonlinearity(rule_number: &Self::UnsignedRepr) -> u32 {
+        let max_absolute_walsh_value = Self::walsh_spectrum(rule_number).iter().map(|walsh_value| walsh_value.unsigned_abs()).max().unwrap_or(0);
+        (1 << (Self::NUM_VARIABLES - 1)) - max_absolute_walsh_value / 2
+    }
+
+    /// Minimum degree of a nonzero annihilator of f or f + 1, returned along with the annihilator found.
+    fn algebraic_immunity(rule_number: &Self::UnsignedRepr) -> (usize, Self::UnsignedRepr) {
+        let truth_table = (0..=Self::MAX_INPUT_VALUE)
+            .map(|x| Self::compute_cellular_automata_rule(rule_number, x))
+            .collect::<Vec<bool>>();
+        let (algebraic_immunity, annihilator) = algebraic_immunity::minimum_degree_annihilator(Self::NUM_VARIABLES, &truth_table);
+        (algebraic_immunity, Self::from_truth_table(&annihilator))
+    }
+
     #[inline]
     fn fast_binary_dot_product(a: u32, b: u32) -> u32 {
         unsafe {
             _popcnt32((a & b) as i32) as u32
         }
     }
+}
+
+/// In-place (unnormalized) Walsh-Hadamard butterfly, values.len() must be a power of two.
+pub(crate) fn walsh_hadamard_butterfly<T: Copy + Add<Output = T> + Sub<Output = T>>(values: &mut [T]) {
+    let mut blocksize = 1usize;
+    while blocksize < values.len() {
+        for block_start in (0..values.len()).step_by(blocksize << 1) {
+            for i in block_start..block_start + blocksize {
+                let (sum, difference) = (values[i] + values[i + blocksize], values[i] - values[i + blocksize]);
+                values[i] = sum;
+                values[i + blocksize] = difference;
+            }
+        }
+        blocksize <<= 1;
+    }
 }
\ No newline at end of file
@@ -1,160 +1,189 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use numext_fixed_uint::U512;
-use rayon::prelude::IntoParallelIterator;
-use rayon::iter::ParallelIterator;
+use std::process::exit;
 use boolean_function_extender::BooleanFunctionTester;
+use boolean_function_extender::affine_equivalence::{classify, EQUIVALENCE_CLASSES};
+use boolean_function_extender::ca_extender::{BoundaryCondition, CaExtender};
+use boolean_function_extender::criteria::CryptographicCriteria;
+use boolean_function_extender::dynamics::StateTransitionGraph;
+use boolean_function_extender::generic_tester::GenericTester;
+use boolean_function_extender::reversibility::analyze_reversibility;
+use boolean_function_extender::symmetry::symmetric_extensions;
 use boolean_function_extender::u32_tester::U32Tester;
 use boolean_function_extender::u512_tester::U512Tester;
+use checkpoint::merge_results;
+use cli::{Command, Criterion};
+use scan::{is_criterion_kept, print_scan_counts, scan};
 
-const RING_SIZE: usize = 9;
-const EQUIVALENCE_CLASSES: [u32; 48] = [0xaa55aa55, 0xaa55ab55, 0xaa55bb55, 0xaa5dbb55, 0xaaddbb55, 0xaa5dbb51, 0x2a5dbb51, 0xaaddbb51, 0x2a5dbf51, 0x6a5dbb51, 0x2addbb51, 0xa8ddbb51, 0xaeddda51, 0x0a5dbf51, 0x8addda51, 0xa8dd9b51, 0x88ddbb51, 0x88ddbb11, 0x8c5dda51, 0xa89d9b51, 0x8eddda51, 0xaefdda51, 0x025dbf51, 0x88ddda51, 0x88dd9b51, 0xceddda51, 0x0eddda51, 0x425dbf51, 0x8cddda51, 0x88dddb51, 0x289d9b51, 0x86fdda51, 0x88dddb71, 0xcefdda51, 0x0efdda51, 0x288d9b51, 0x8cfdda51, 0x8cdddb51, 0x8ccdda51, 0x289d9b41, 0x488ddb51, 0xccfdda51, 0x688d9b51, 0x288d9b41, 0x288d1b41, 0xdcfdda51, 0x68ad9b51, 0x688ddb51];
+mod checkpoint;
+mod cli;
+mod scan;
+
+const NEIGHBOURHOOD_RADIUS: usize = 2;
+// Ring maps of larger rings are too large for the vectorial analyses
+const MAX_VECTORIAL_RING_SIZE: usize = 9;
 
 fn main() {
-    // SAC, 1st order CI, balanced, prop crit 2, prop crit 3, prop 4, prop 5, better deg
-    let mut classes_eq_count: Vec<(u32, HashMap<u32, usize>, HashMap<u32, usize>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>, Arc<Mutex<usize>>)> = Vec::new();
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let (extension_options, command) = match cli::parse_args(&args) {
+        Ok(parsed_args) => parsed_args,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            exit(2);
+        }
+    };
+    let ca_extender = CaExtender::new(extension_options.ring_size, NEIGHBOURHOOD_RADIUS, extension_options.iterations, extension_options.observed_cell)
+        .with_boundary_condition(extension_options.boundary_condition);
 
-    for eq in EQUIVALENCE_CLASSES {
-        let walsh_spectrum_eq = U32Tester::absolute_walsh_spectrum(&eq);
-        let autocorrelation_spectrum_eq = U32Tester::absolute_autocorrelation_spectrum(&eq);
-        classes_eq_count.push((eq, walsh_spectrum_eq, autocorrelation_spectrum_eq, Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0)), Arc::new(Mutex::new(0))));
+    // Extended functions are represented by the tester of ring_size variables
+    match extension_options.ring_size {
+        5 => run::<U32Tester>(&ca_extender, &command),
+        6 => run::<GenericTester<6>>(&ca_extender, &command),
+        7 => run::<GenericTester<7>>(&ca_extender, &command),
+        8 => run::<GenericTester<8>>(&ca_extender, &command),
+        9 => run::<U512Tester>(&ca_extender, &command),
+        10 => run::<GenericTester<10>>(&ca_extender, &command),
+        11 => run::<GenericTester<11>>(&ca_extender, &command),
+        12 => run::<GenericTester<12>>(&ca_extender, &command),
+        13 => run::<GenericTester<13>>(&ca_extender, &command),
+        14 => run::<GenericTester<14>>(&ca_extender, &command),
+        15 => run::<GenericTester<15>>(&ca_extender, &command),
+        16 => run::<GenericTester<16>>(&ca_extender, &command),
+        ring_size => panic!("Unsupported ring size {}", ring_size),
     }
+}
 
-    (0..=u32::MAX).into_par_iter().for_each(|rule_number| {
-        let walsh_spectrum = U32Tester::absolute_walsh_spectrum(&rule_number);
-        let autocorrelation_spectrum = U32Tester::absolute_autocorrelation_spectrum(&rule_number);
-        let output_9_rule_number = extend_rule_5_to_9(rule_number);
-        let mut equivalent_class_index = 0;
-        for (index, eq) in classes_eq_count.iter().enumerate() {
-            let walsh_spectrum_eq = &eq.1;
-            let autocorrelation_spectrum_eq = &eq.2;
-            if walsh_spectrum == *walsh_spectrum_eq && autocorrelation_spectrum == *autocorrelation_spectrum_eq {
-                equivalent_class_index = index;
-                break;
-            }
-        }
-
-        if U512Tester::is_strict_avalanche_criterion_ok(&output_9_rule_number) && U32Tester::is_strict_avalanche_criterion_ok(&rule_number) {
-            *classes_eq_count[equivalent_class_index].3.lock().unwrap() += 1;
-        }
-        if U512Tester::is_first_order_correlation_immune(&output_9_rule_number) && U32Tester::is_first_order_correlation_immune(&rule_number) {
-            *classes_eq_count[equivalent_class_index].4.lock().unwrap() += 1;
-        }
-        if U512Tester::is_function_balanced(&output_9_rule_number) && U32Tester::is_function_balanced(&rule_number) {
-            *classes_eq_count[equivalent_class_index].5.lock().unwrap() += 1;
-        }
-        if U512Tester::is_propagation_criterion_deg_k_ok(&output_9_rule_number, 2) && U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, 2) {
-            *classes_eq_count[equivalent_class_index].6.lock().unwrap() += 1;
-        }
-        if U512Tester::is_propagation_criterion_deg_k_ok(&output_9_rule_number, 3) && U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, 3) {
-            *classes_eq_count[equivalent_class_index].7.lock().unwrap() += 1;
-        }
-        if U512Tester::is_propagation_criterion_deg_k_ok(&output_9_rule_number, 4) && U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, 4) {
-            *classes_eq_count[equivalent_class_index].8.lock().unwrap() += 1;
+fn run<T: BooleanFunctionTester>(ca_extender: &CaExtender, command: &Command)
+where
+    T::UnsignedRepr: PartialEq,
+{
+    match command {
+        Command::Analyze(rule_number) => print_analysis::<T>(ca_extender, *rule_number),
+        Command::Extend(rule_number) => print_extension(ca_extender, *rule_number),
+        Command::Classify(rule_number) => print_classification(*rule_number),
+        Command::Report => {
+            print_boundary_condition_report::<T>(ca_extender);
+            print_vectorial_report(ca_extender);
+            print_symmetry_report::<T>(ca_extender);
         }
-        if U512Tester::is_propagation_criterion_deg_k_ok(&output_9_rule_number, 5) && U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, 5) {
-            *classes_eq_count[equivalent_class_index].9.lock().unwrap() += 1;
-        }
-        if U512Tester::get_function_degree(&output_9_rule_number) >= U32Tester::get_function_degree(&rule_number) {
-            *classes_eq_count[equivalent_class_index].10.lock().unwrap() += 1;
-        }
-    });
-
-    println!("-----------------------------------");
-    println!("SAC:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.3.lock().unwrap());
-    }
-    println!("-----------------------------------");
-    println!("1st order CI:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.4.lock().unwrap());
-    }
-    println!("-----------------------------------");
-    println!("Balanced:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.5.lock().unwrap());
-    }
-    println!("-----------------------------------");
-    println!("Prop crit 2:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.6.lock().unwrap());
-    }
-    println!("-----------------------------------");
-    println!("Prop crit 3:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.7.lock().unwrap());
-    }
-    println!("-----------------------------------");
-    println!("Prop crit 4:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.8.lock().unwrap());
+        Command::Scan(scan_options) => match scan::<T>(ca_extender, scan_options) {
+            Ok(scan_counts) => print_scan_counts(&scan_counts, scan_options),
+            Err(error) => {
+                eprintln!("{}", error);
+                exit(1);
+            }
+        },
+        Command::Merge(result_paths) => match merge_results(result_paths) {
+            Ok(merged_result) => print_scan_counts(&merged_result.scan_counts, &merged_result.scan_options()),
+            Err(error) => {
+                eprintln!("{}", error);
+                exit(1);
+            }
+        },
     }
-    println!("-----------------------------------");
-    println!("Prop crit 5:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.9.lock().unwrap());
+}
+
+fn print_analysis<T: BooleanFunctionTester>(ca_extender: &CaExtender, rule_number: u32) {
+    println!("Rule {:#010x}: {}", rule_number, CryptographicCriteria::compute::<U32Tester>(&rule_number));
+    match classify(rule_number) {
+        Ok(classification) => println!("Equivalence class: {} ({:#010x})", classification.class_index, classification.representative),
+        Err(error) => println!("Equivalence class: {}", error),
     }
-    println!("-----------------------------------");
-    println!("Eq or better deg:");
-    for eq in &classes_eq_count {
-        println!("{:#02x} -> {}", eq.0, eq.10.lock().unwrap());
+    let extended_rule_number = ca_extender.extend::<U32Tester, T>(&rule_number);
+    println!("Extension: {}", CryptographicCriteria::compute::<T>(&extended_rule_number));
+    let kept_criteria = Criterion::ALL.iter()
+        .filter(|criterion| is_criterion_kept::<T>(criterion, rule_number, &extended_rule_number))
+        .map(|criterion| criterion.name())
+        .collect::<Vec<String>>();
+    println!("Kept criteria: {}", kept_criteria.join(", "));
+    if ca_extender.ring_size() <= MAX_VECTORIAL_RING_SIZE {
+        print_ring_map_analysis(ca_extender, rule_number);
+        let state_transition_graph = StateTransitionGraph::from_ca_extender(ca_extender, rule_number);
+        println!("Ring map dynamics: {} cycles, max transient length: {}, garden of Eden states: {}",
+                 state_transition_graph.cycles_count(), state_transition_graph.max_transient_length(), state_transition_graph.garden_of_eden_states().len());
     }
 }
 
-fn extend_rule_5_to_9(rule_number: u32) -> U512 {
-    let mut output_rule_number = U512::zero();
-    for i in 0usize..(1 << RING_SIZE) {
-        let input_bits = unsigned_to_bool_array::<RING_SIZE>(i);
-        let round_1 = get_new_ring(input_bits, rule_number);
-        let round_2 = get_new_ring(round_1, rule_number);
-        if round_2[4] {
-            output_rule_number |= U512::one() << i;
-            //print!("1");
-        } else {
-            //print!("0");
+fn print_extension(ca_extender: &CaExtender, rule_number: u32) {
+    let truth_table = ca_extender.extend_truth_table::<U32Tester>(&rule_number);
+    // Most significant nibble first, bit x of the number being the value of the input x
+    let hex_digits = truth_table.chunks(4).rev()
+        .map(|nibble| {
+            let digit = nibble.iter().rev().fold(0u32, |digit, &value| digit << 1 | value as u32);
+            char::from_digit(digit, 16).unwrap()
+        })
+        .collect::<String>();
+    println!("0x{}", hex_digits);
+}
+
+fn print_classification(rule_number: u32) {
+    match classify(rule_number) {
+        Ok(classification) => {
+            let witness = classification.witness;
+            println!("Equivalence class: {} ({:#010x})", classification.class_index, classification.representative);
+            println!("Rule = representative(A x + b) + l.x + c with A columns: {:?}, b: {:#x}, l: {:#x}, c: {}",
+                     witness.matrix, witness.b, witness.l, witness.c as u8);
+        }
+        Err(error) => {
+            eprintln!("Rule {:#010x} is not classified: {}", rule_number, error);
+            exit(1);
         }
     }
-    output_rule_number
 }
 
-
-
-#[inline(always)]
-fn compute_ca_rule(rule_number: u32, input_bits: u8) -> bool {
-    return (rule_number & (1 << input_bits)) != 0;
+fn print_boundary_condition_report<T: BooleanFunctionTester>(ca_extender: &CaExtender) {
+    println!("-----------------------------------");
+    println!("Extension criteria per boundary condition:");
+    for eq in EQUIVALENCE_CLASSES {
+        println!("{:#02x} -> {}", eq, CryptographicCriteria::compute::<U32Tester>(&eq));
+        for boundary_condition in BoundaryCondition::ALL {
+            let ca_extender = CaExtender::new(ca_extender.ring_size(), NEIGHBOURHOOD_RADIUS, ca_extender.iterations(), ca_extender.observed_cell())
+                .with_boundary_condition(boundary_condition);
+            let extended_rule_number = ca_extender.extend::<U32Tester, T>(&eq);
+            println!("    {} -> {}", boundary_condition, CryptographicCriteria::compute::<T>(&extended_rule_number));
+        }
+    }
 }
 
-fn get_new_ring(ring: [bool; RING_SIZE], rule_number: u32) -> [bool; RING_SIZE] {
-    let mut new_ring = [false; RING_SIZE];
-    for i in 0..RING_SIZE {
-        let input_bits = (ring[(i + RING_SIZE - 2) % RING_SIZE] as u8) << 4
-            | (ring[(i + RING_SIZE - 1) % RING_SIZE] as u8) << 3
-            | (ring[i] as u8) << 2
-            | (ring[(i + 1) % RING_SIZE] as u8) << 1
-            | ring[(i + 2) % RING_SIZE] as u8;
-        new_ring[i] = compute_ca_rule(rule_number, input_bits);
+fn print_vectorial_report(ca_extender: &CaExtender) {
+    if ca_extender.ring_size() > MAX_VECTORIAL_RING_SIZE {
+        return;
+    }
+    println!("-----------------------------------");
+    println!("Ring after {} iterations as a {}-bit S-box:", ca_extender.iterations(), ca_extender.ring_size());
+    for eq in EQUIVALENCE_CLASSES {
+        print!("{:#02x} -> ", eq);
+        print_ring_map_analysis(ca_extender, eq);
     }
-    new_ring
 }
 
-#[inline(always)]
-fn unsigned_to_bool_array<const S: usize>(number: usize) -> [bool; S] {
-    let mut bits = [false; S];
-    for i in 0..S {
-        bits[i] = (number & (1 << i)) != 0;
-    }
-    bits
+fn print_ring_map_analysis(ca_extender: &CaExtender, rule_number: u32) {
+    let ring_map = ca_extender.extend_vectorial::<U32Tester>(&rule_number);
+    let boomerang_uniformity = match ring_map.boomerang_uniformity() {
+        Some(boomerang_uniformity) => boomerang_uniformity.to_string(),
+        None => "-".to_string(),
+    };
+    let ring_map_inverse_degree = match analyze_reversibility(ca_extender, rule_number) {
+        Some(ring_map_inverse) => ring_map_inverse.inverse_degree.to_string(),
+        None => "-".to_string(),
+    };
+    println!("ring map inverse degree: {}, differential uniformity: {}, nonlinearity: {}, boomerang uniformity: {}",
+             ring_map_inverse_degree, ring_map.differential_uniformity(), ring_map.nonlinearity(), boomerang_uniformity);
 }
 
-#[allow(dead_code)]
-#[inline(always)]
-fn bool_array_to_unsigned<const S: usize>(bits: [bool; S]) -> usize {
-    let mut number = 0;
-    for i in 0..S {
-        if bits[i] {
-            number |= 1 << i;
-        }
+fn print_symmetry_report<T: BooleanFunctionTester>(ca_extender: &CaExtender)
+where
+    T::UnsignedRepr: PartialEq,
+{
+    println!("-----------------------------------");
+    println!("Extensions of the symmetric rules checked against the symmetric transforms:");
+    for eq in EQUIVALENCE_CLASSES {
+        let extended_rule_number = ca_extender.extend::<U32Tester, T>(&eq);
+        let symmetry_checks = symmetric_extensions::<T>(ca_extender, eq, &extended_rule_number)
+            .into_iter()
+            .map(|(symmetry, symmetric_rule_number, symmetric_extended_rule_number)| {
+                let symmetry_ok = ca_extender.extend::<U32Tester, T>(&symmetric_rule_number) == symmetric_extended_rule_number;
+                format!("{} {:#010x}: {}", symmetry, symmetric_rule_number, if symmetry_ok { "ok" } else { "mismatch" })
+            })
+            .collect::<Vec<String>>();
+        println!("{:#02x} -> {}", eq, symmetry_checks.join(", "));
     }
-    number
 }
@@ -0,0 +1,84 @@
+use std::ops::RangeInclusive;
+use crate::ca_extender::CaExtender;
+use crate::symmetry::Symmetry;
+
+/// Orbits of the 5-variable rules under the neighbourhood symmetries preserved by an extender.
+/// Rules of the same orbit lie in the same affine equivalence class, and their extended functions are related by a
+/// permutation of the variables and complementations, so they share every criterion computed by the experiment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleOrbits {
+    symmetries: Vec<Symmetry>,
+}
+
+impl RuleOrbits {
+    pub fn for_ca_extender(ca_extender: &CaExtender) -> Self {
+        RuleOrbits {
+            symmetries: Symmetry::preserved_by(ca_extender),
+        }
+    }
+
+    /// Rules of the orbit of the rule, sorted and without duplicates.
+    pub fn orbit(&self, rule_number: u32) -> Vec<u32> {
+        let mut orbit = self.symmetries.iter().map(|symmetry| symmetry.apply_to_rule(rule_number)).collect::<Vec<u32>>();
+        orbit.sort_unstable();
+        orbit.dedup();
+        orbit
+    }
+
+    /// Orbit size if the rule is the smallest rule of its orbit, None otherwise, so that enumerating every
+    /// rule and weighting the representatives visits each rule exactly once.
+    pub fn representative_weight(&self, rule_number: u32) -> Option<usize> {
+        self.representative_weight_in(rule_number, &(0..=u32::MAX))
+    }
+
+    /// Same as representative_weight, restricted to the orbit rules lying in the range of enumerated rules.
+    pub fn representative_weight_in(&self, rule_number: u32, rules: &RangeInclusive<u32>) -> Option<usize> {
+        let orbit = self.orbit(rule_number).into_iter().filter(|rule| rules.contains(rule)).collect::<Vec<u32>>();
+        if orbit.first() == Some(&rule_number) {
+            Some(orbit.len())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::affine_equivalence::canonical_affine_representative;
+    use crate::ca_extender::{BoundaryCondition, CaExtender};
+    use crate::criteria::CryptographicCriteria;
+    use crate::u32_tester::U32Tester;
+    use crate::u512_tester::U512Tester;
+    use super::RuleOrbits;
+
+    #[test]
+    fn test_orbits() {
+        let rule_orbits = RuleOrbits::for_ca_extender(&CaExtender::new(9, 2, 2, 4));
+        assert_eq!(rule_orbits.orbit(0xffff0000), vec![0xaaaaaaaa, 0xffff0000]);
+        assert_eq!(rule_orbits.representative_weight(0xaaaaaaaa), Some(2));
+        assert_eq!(rule_orbits.representative_weight(0xffff0000), None);
+        assert_eq!(rule_orbits.representative_weight_in(0xffff0000, &(0xb0000000..=0xffffffff)), Some(1));
+        assert_eq!(rule_orbits.representative_weight_in(0xaaaaaaaa, &(0xb0000000..=0xffffffff)), None);
+        assert_eq!(rule_orbits.orbit(0x8ccdda51).len(), 4);
+
+        let rule_orbits = RuleOrbits::for_ca_extender(&CaExtender::new(9, 2, 2, 4).with_boundary_condition(BoundaryCondition::Null));
+        assert_eq!(rule_orbits.orbit(0), vec![0]);
+        let rule_orbits = RuleOrbits::for_ca_extender(&CaExtender::new(9, 2, 2, 0));
+        assert_eq!(rule_orbits.orbit(0xffff0000), vec![0xffff0000]);
+    }
+
+    #[test]
+    fn test_orbit_criteria() {
+        for boundary_condition in [BoundaryCondition::Periodic, BoundaryCondition::Null, BoundaryCondition::Reflective] {
+            let ca_extender = CaExtender::new(9, 2, 2, 4).with_boundary_condition(boundary_condition);
+            let rule_orbits = RuleOrbits::for_ca_extender(&ca_extender);
+            for rule_number in [0x8ccdda51, 3755921403] {
+                let criteria = rule_orbits.orbit(rule_number).iter()
+                    .map(|rule| CryptographicCriteria::compute::<U512Tester>(&ca_extender.extend::<U32Tester, U512Tester>(rule)))
+                    .collect::<Vec<CryptographicCriteria>>();
+                assert!(criteria.iter().all(|orbit_criteria| *orbit_criteria == criteria[0]));
+                assert!(rule_orbits.orbit(rule_number).iter().all(|&rule| canonical_affine_representative(rule) == canonical_affine_representative(rule_number)));
+            }
+        }
+    }
+}
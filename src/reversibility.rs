@@ -0,0 +1,52 @@
+use crate::ca_extender::CaExtender;
+use crate::u32_tester::U32Tester;
+use crate::vectorial::VectorialFunction;
+
+/// Inverse of a bijective global ring map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingMapInverse {
+    pub inverse_map: VectorialFunction,
+    pub inverse_degree: usize,
+}
+
+/// Determines whether the global map applying the 5-variable rule once to every cell of a periodic ring
+/// of ring_size cells is bijective, and if so returns its inverse map along with the inverse algebraic degree.
+pub fn analyze_ring_map_reversibility(rule_number: u32, ring_size: usize) -> Option<RingMapInverse> {
+    analyze_reversibility(&CaExtender::new(ring_size, 2, 1, 0), rule_number)
+}
+
+/// Inverse of the map applying the rule once to every cell of the extender ring, under its boundary condition,
+/// None when that ring map is not bijective. The iterations and observed cell of the extender are ignored.
+pub fn analyze_reversibility(ca_extender: &CaExtender, rule_number: u32) -> Option<RingMapInverse> {
+    let inverse_map = ca_extender.ring_map::<U32Tester>(&rule_number).inverse()?;
+    let inverse_degree = inverse_map.algebraic_degree();
+    Some(RingMapInverse {
+        inverse_map,
+        inverse_degree,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ca_extender::CaExtender;
+    use crate::u32_tester::U32Tester;
+    use super::analyze_ring_map_reversibility;
+
+    #[test]
+    fn test_analyze_ring_map_reversibility() {
+        // Leftmost neighbour rule: the ring map is a rotation, the inverse is the opposite rotation
+        let ring_map_inverse = analyze_ring_map_reversibility(0xffff0000, 9).unwrap();
+        assert_eq!(ring_map_inverse.inverse_degree, 1);
+        assert_eq!(ring_map_inverse.inverse_map.evaluate(0b000000100), 0b000000001);
+
+        // x_i + (x_(i+1) + 1) x_(i+2), the Keccak chi map, is invertible on odd ring sizes
+        let chi_rule = 0xd2d2d2d2;
+        let ring_map_inverse = analyze_ring_map_reversibility(chi_rule, 9).unwrap();
+        assert_eq!(ring_map_inverse.inverse_degree, 5);
+        let ring_map = CaExtender::new(9, 2, 1, 0).ring_map::<U32Tester>(&chi_rule);
+        assert!((0..512).all(|x| ring_map_inverse.inverse_map.evaluate(ring_map.evaluate(x)) == x));
+        assert_eq!(analyze_ring_map_reversibility(chi_rule, 8), None);
+
+        assert_eq!(analyze_ring_map_reversibility(3755921403, 9), None);
+    }
+}
@@ -0,0 +1,226 @@
+use std::ops::RangeInclusive;
+use rayon::prelude::IntoParallelIterator;
+use rayon::iter::ParallelIterator;
+use boolean_function_extender::BooleanFunctionTester;
+use boolean_function_extender::affine_equivalence::{classify, EQUIVALENCE_CLASSES};
+use boolean_function_extender::ca_extender::CaExtender;
+use boolean_function_extender::orbits::RuleOrbits;
+use boolean_function_extender::symmetry::symmetric_extensions;
+use boolean_function_extender::u32_tester::U32Tester;
+use crate::checkpoint::Checkpoint;
+use crate::cli::{Criterion, ScanOptions};
+
+/// Scan counters: per class, the number of rules keeping each scanned criterion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanCounts {
+    pub class_counts: Vec<Vec<usize>>,
+    pub unclassified_count: usize,
+    pub symmetry_mismatch_count: usize,
+}
+
+impl ScanCounts {
+    pub fn new(criteria_count: usize) -> Self {
+        ScanCounts {
+            class_counts: vec![vec![0; criteria_count]; EQUIVALENCE_CLASSES.len()],
+            unclassified_count: 0,
+            symmetry_mismatch_count: 0,
+        }
+    }
+
+    pub fn merge(mut self, other: ScanCounts) -> Self {
+        self.class_counts.iter_mut().zip(other.class_counts).for_each(|(counts, other_counts)| {
+            counts.iter_mut().zip(other_counts).for_each(|(count, other_count)| *count += other_count);
+        });
+        self.unclassified_count += other.unclassified_count;
+        self.symmetry_mismatch_count += other.symmetry_mismatch_count;
+        self
+    }
+}
+
+/// Scans the rule range of the shard, by blocks of checkpoint_interval rules when a checkpoint file is given, the counts
+/// and the next rule to scan being written to the file after each block.
+pub fn scan<T: BooleanFunctionTester>(ca_extender: &CaExtender, scan_options: &ScanOptions) -> Result<ScanCounts, String>
+where
+    T::UnsignedRepr: PartialEq,
+{
+    let mut checkpoint = Checkpoint::new(ca_extender, scan_options);
+    if scan_options.resume {
+        let checkpoint_path = scan_options.checkpoint_path.as_ref().unwrap();
+        let saved_checkpoint = Checkpoint::read(checkpoint_path)?;
+        if !saved_checkpoint.has_same_scan(&checkpoint) {
+            return Err(format!("Checkpoint {} was written by a scan with other options", checkpoint_path.display()));
+        }
+        checkpoint = saved_checkpoint;
+    }
+
+    let block_size = match scan_options.checkpoint_path {
+        Some(_) => scan_options.checkpoint_interval,
+        None => 1 << 32,
+    };
+    let shard_end = *scan_options.shard_range().end();
+    while let Some(next_rule) = checkpoint.next_rule {
+        let block_end = (next_rule as u64 + block_size - 1).min(shard_end as u64) as u32;
+        let block_counts = scan_block::<T>(ca_extender, scan_options, next_rule..=block_end);
+        checkpoint.scan_counts = checkpoint.scan_counts.merge(block_counts);
+        checkpoint.next_rule = if block_end < shard_end { Some(block_end + 1) } else { None };
+        if let Some(checkpoint_path) = &scan_options.checkpoint_path {
+            checkpoint.write(checkpoint_path)?;
+        }
+    }
+    if let Some(output_path) = &scan_options.output_path {
+        checkpoint.write(output_path)?;
+    }
+    Ok(checkpoint.scan_counts)
+}
+
+fn scan_block<T: BooleanFunctionTester>(ca_extender: &CaExtender, scan_options: &ScanOptions, block: RangeInclusive<u32>) -> ScanCounts
+where
+    T::UnsignedRepr: PartialEq,
+{
+    // Orbits are restricted to the whole scanned range, so that the counts do not depend on the blocks and shards
+    let rules = scan_options.from..=scan_options.to;
+    let rule_orbits = RuleOrbits::for_ca_extender(ca_extender);
+    let criteria_count = scan_options.criteria.len();
+
+    block.into_par_iter()
+        .fold(|| ScanCounts::new(criteria_count), |mut scan_counts, rule_number| {
+            // Extends only the smallest rule of each orbit under the neighbourhood symmetries, weighted by the orbit size
+            let weight = if scan_options.enumerate_orbits {
+                match rule_orbits.representative_weight_in(rule_number, &rules) {
+                    Some(weight) => weight,
+                    None => return scan_counts,
+                }
+            } else {
+                1
+            };
+            let equivalent_class_index = match classify(rule_number) {
+                Ok(classification) => classification.class_index,
+                Err(error) => {
+                    eprintln!("Rule {:#010x} is not classified: {}", rule_number, error);
+                    scan_counts.unclassified_count += weight;
+                    return scan_counts;
+                }
+            };
+            if scan_options.classes.as_ref().is_some_and(|classes| !classes.contains(&equivalent_class_index)) {
+                return scan_counts;
+            }
+            let extended_rule_number = ca_extender.extend::<U32Tester, T>(&rule_number);
+            if scan_options.cross_check_symmetries {
+                for (symmetry, symmetric_rule_number, symmetric_extended_rule_number) in symmetric_extensions::<T>(ca_extender, rule_number, &extended_rule_number) {
+                    if ca_extender.extend::<U32Tester, T>(&symmetric_rule_number) != symmetric_extended_rule_number {
+                        eprintln!("Rule {:#010x} extension does not match its {} transform", symmetric_rule_number, symmetry);
+                        scan_counts.symmetry_mismatch_count += 1;
+                    }
+                }
+            }
+            for (criterion, count) in scan_options.criteria.iter().zip(scan_counts.class_counts[equivalent_class_index].iter_mut()) {
+                if is_criterion_kept::<T>(criterion, rule_number, &extended_rule_number) {
+                    *count += weight;
+                }
+            }
+            scan_counts
+        })
+        .reduce(|| ScanCounts::new(criteria_count), ScanCounts::merge)
+}
+
+pub fn is_criterion_kept<T: BooleanFunctionTester>(criterion: &Criterion, rule_number: u32, extended_rule_number: &T::UnsignedRepr) -> bool {
+    let extra_variables = T::NUM_VARIABLES - U32Tester::NUM_VARIABLES;
+    match criterion {
+        Criterion::StrictAvalanche => T::is_strict_avalanche_criterion_ok(extended_rule_number) && U32Tester::is_strict_avalanche_criterion_ok(&rule_number),
+        Criterion::FirstOrderCorrelationImmunity => T::is_first_order_correlation_immune(extended_rule_number) && U32Tester::is_first_order_correlation_immune(&rule_number),
+        Criterion::Balanced => T::is_function_balanced(extended_rule_number) && U32Tester::is_function_balanced(&rule_number),
+        Criterion::Propagation(degree) => T::is_propagation_criterion_deg_k_ok(extended_rule_number, *degree) && U32Tester::is_propagation_criterion_deg_k_ok(&rule_number, *degree),
+        Criterion::Degree => T::get_function_degree(extended_rule_number) >= U32Tester::get_function_degree(&rule_number),
+        Criterion::Nonlinearity => T::nonlinearity(extended_rule_number) >= U32Tester::nonlinearity(&rule_number),
+        // Only counted when both functions are resilient
+        Criterion::Resiliency => match (T::resiliency_order(extended_rule_number), U32Tester::resiliency_order(&rule_number)) {
+            (Some(extended_resiliency_order), Some(resiliency_order)) => extended_resiliency_order >= resiliency_order,
+            _ => false,
+        },
+        // GAC indicators are compared relatively to their maximum value, 2^n and 2^(3n) respectively
+        Criterion::AbsoluteIndicator => T::absolute_indicator(extended_rule_number) <= U32Tester::absolute_indicator(&rule_number) << extra_variables,
+        Criterion::SumOfSquaresIndicator => T::sum_of_squares_indicator(extended_rule_number) <= U32Tester::sum_of_squares_indicator(&rule_number) << (3 * extra_variables),
+    }
+}
+
+pub fn print_scan_counts(scan_counts: &ScanCounts, scan_options: &ScanOptions) {
+    println!("-----------------------------------");
+    println!("Unclassified rules (excluded from statistics): {}", scan_counts.unclassified_count);
+    if scan_options.cross_check_symmetries {
+        println!("Symmetric extension mismatches: {}", scan_counts.symmetry_mismatch_count);
+    }
+    for (criterion_index, criterion) in scan_options.criteria.iter().enumerate() {
+        println!("-----------------------------------");
+        println!("{}:", criterion);
+        for (class_index, eq) in EQUIVALENCE_CLASSES.iter().enumerate() {
+            if scan_options.classes.as_ref().is_none_or(|classes| classes.contains(&class_index)) {
+                println!("{:#02x} -> {}", eq, scan_counts.class_counts[class_index][criterion_index]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boolean_function_extender::ca_extender::CaExtender;
+    use boolean_function_extender::u32_tester::U32Tester;
+    use crate::checkpoint::{merge_results, Checkpoint};
+    use crate::cli::{parse_args, Command, ScanOptions};
+    use super::{scan, scan_block};
+
+    fn scan_options(command_line: &str) -> ScanOptions {
+        let args = command_line.split_whitespace().map(String::from).collect::<Vec<String>>();
+        match parse_args(&args).unwrap().1 {
+            Command::Scan(scan_options) => scan_options,
+            _ => panic!("Expected a scan command"),
+        }
+    }
+
+    #[test]
+    fn test_scan_orbits() {
+        // Ring of 5 cells, extending to 5-variable functions, the range holding both rules of some mirror orbits
+        let ca_extender = CaExtender::new(5, 2, 2, 2);
+        let scan_counts = scan::<U32Tester>(&ca_extender, &scan_options("scan --from 0xaaaa0000 --to 0xaaaa0fff --no-orbits")).unwrap();
+        assert_eq!(scan_counts.class_counts.iter().map(|counts| counts[2]).sum::<usize>(), 142);
+        assert_eq!(scan::<U32Tester>(&ca_extender, &scan_options("scan --from 0xaaaa0000 --to 0xaaaa0fff")).unwrap(), scan_counts);
+    }
+
+    #[test]
+    fn test_scan_checkpoints() {
+        let ca_extender = CaExtender::new(5, 2, 2, 2);
+        let checkpoint_path = std::env::temp_dir().join(format!("scan_checkpoint_{}.txt", std::process::id()));
+        let options = format!("scan --from 0x7fffff00 --to 0x800000ff --checkpoint {} --checkpoint-interval 100", checkpoint_path.display());
+        let scan_counts = scan::<U32Tester>(&ca_extender, &scan_options("scan --from 0x7fffff00 --to 0x800000ff")).unwrap();
+        assert_eq!(scan::<U32Tester>(&ca_extender, &scan_options(&options)).unwrap(), scan_counts);
+
+        // Interrupted scan, resumed from the middle of the range
+        let mut checkpoint = Checkpoint::new(&ca_extender, &scan_options(&options));
+        checkpoint.scan_counts = scan_block::<U32Tester>(&ca_extender, &scan_options(&options), 0x7fffff00..=0x7fffffff);
+        checkpoint.next_rule = Some(0x80000000);
+        checkpoint.write(&checkpoint_path).unwrap();
+        assert_eq!(scan::<U32Tester>(&ca_extender, &scan_options(&format!("{} --resume", options))).unwrap(), scan_counts);
+        // Resuming a finished scan gives back the final counts
+        assert_eq!(scan::<U32Tester>(&ca_extender, &scan_options(&format!("{} --resume", options))).unwrap(), scan_counts);
+        assert!(scan::<U32Tester>(&ca_extender, &scan_options(&format!("{} --resume --criteria sac", options))).is_err());
+        std::fs::remove_file(checkpoint_path).unwrap();
+    }
+
+    #[test]
+    fn test_sharded_scan() {
+        let ca_extender = CaExtender::new(5, 2, 2, 2);
+        let scan_counts = scan::<U32Tester>(&ca_extender, &scan_options("scan --from 0xaaaa0000 --to 0xaaaa0fff")).unwrap();
+        let result_paths = (0..3)
+            .map(|shard_index| {
+                let result_path = std::env::temp_dir().join(format!("scan_result_{}_{}.txt", std::process::id(), shard_index));
+                let options = format!("scan --from 0xaaaa0000 --to 0xaaaa0fff --shard-index {} --shard-count 3 --output {}", shard_index, result_path.display());
+                scan::<U32Tester>(&ca_extender, &scan_options(&options)).unwrap();
+                result_path
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(merge_results(&result_paths).unwrap().scan_counts, scan_counts);
+        assert!(merge_results(&result_paths[..2]).unwrap_err().contains("not covered"));
+        assert_eq!(merge_results(&[]).unwrap_err(), "No result file to merge");
+        assert!(merge_results(&[result_paths[0].clone(), result_paths[0].clone(), result_paths[1].clone(), result_paths[2].clone()]).unwrap_err().contains("overlaps"));
+        result_paths.iter().for_each(|result_path| std::fs::remove_file(result_path).unwrap());
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+use crate::BooleanFunctionTester;
+use crate::ca_extender::{BoundaryCondition, CaExtender};
+
+/// Symmetries of the radius-2 neighbourhood. Applying a symmetry to the rule applies the corresponding
+/// symmetry of the ring to the extended function, provided the extender preserves it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    Identity,
+    /// Leftmost and rightmost neighbours are swapped: cell i of the ring becomes cell ring_size - 1 - i.
+    Mirror,
+    /// Input and output are complemented.
+    Conjugation,
+    MirrorConjugation,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 4] = [Symmetry::Identity, Symmetry::Mirror, Symmetry::Conjugation, Symmetry::MirrorConjugation];
+
+    /// Whether the extension commutes with the symmetry: the mirror needs the observed cell to be the centre
+    /// of the ring, the conjugation needs a boundary condition that does not depend on the cell values.
+    pub fn is_preserved_by(&self, ca_extender: &CaExtender) -> bool {
+        if ca_extender.radius() != 2 {
+            panic!("Symmetries are defined for radius 2");
+        }
+        let mirror_preserved = 2 * ca_extender.observed_cell() + 1 == ca_extender.ring_size();
+        let conjugation_preserved = matches!(ca_extender.boundary_condition(), BoundaryCondition::Periodic | BoundaryCondition::Reflective);
+        match self {
+            Symmetry::Identity => true,
+            Symmetry::Mirror => mirror_preserved,
+            Symmetry::Conjugation => conjugation_preserved,
+            Symmetry::MirrorConjugation => mirror_preserved && conjugation_preserved,
+        }
+    }
+
+    /// Symmetries preserved by the extender.
+    pub fn preserved_by(ca_extender: &CaExtender) -> Vec<Symmetry> {
+        Symmetry::ALL.into_iter().filter(|symmetry| symmetry.is_preserved_by(ca_extender)).collect()
+    }
+
+    pub fn apply_to_rule(&self, rule_number: u32) -> u32 {
+        match self {
+            Symmetry::Identity => rule_number,
+            Symmetry::Mirror => mirror_rule(rule_number),
+            Symmetry::Conjugation => conjugate_rule(rule_number),
+            Symmetry::MirrorConjugation => conjugate_rule(mirror_rule(rule_number)),
+        }
+    }
+
+    /// Transform of the extended function of T::NUM_VARIABLES variables matching the transform of the rule:
+    /// the mirror reverses the variables order, the conjugation complements the input and the output.
+    pub fn apply_to_extension<T: BooleanFunctionTester>(&self, rule_number: &T::UnsignedRepr) -> T::UnsignedRepr {
+        let num_variables = T::NUM_VARIABLES as u32;
+        let (mirror, conjugation) = match self {
+            Symmetry::Identity => (false, false),
+            Symmetry::Mirror => (true, false),
+            Symmetry::Conjugation => (false, true),
+            Symmetry::MirrorConjugation => (true, true),
+        };
+        let truth_table = (0..=T::MAX_INPUT_VALUE)
+            .map(|x| {
+                let x = if mirror { x.reverse_bits() >> (32 - num_variables) } else { x };
+                let x = if conjugation { x ^ T::MAX_INPUT_VALUE } else { x };
+                T::compute_cellular_automata_rule(rule_number, x) ^ conjugation
+            })
+            .collect::<Vec<bool>>();
+        T::from_truth_table(&truth_table)
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Symmetry::Identity => write!(f, "identity"),
+            Symmetry::Mirror => write!(f, "mirror"),
+            Symmetry::Conjugation => write!(f, "conjugation"),
+            Symmetry::MirrorConjugation => write!(f, "mirror conjugation"),
+        }
+    }
+}
+
+/// Rule with the neighbour order reversed, bit i of the rule moving to the bit-reversed position of i.
+pub fn mirror_rule(rule_number: u32) -> u32 {
+    (0..32u32).fold(0, |mirrored_rule, input_bits| {
+        let mirrored_input_bits = input_bits.reverse_bits() >> 27;
+        mirrored_rule | (rule_number >> input_bits & 1) << mirrored_input_bits
+    })
+}
+
+/// Rule computing the complement of the rule on the complemented neighbourhood.
+pub fn conjugate_rule(rule_number: u32) -> u32 {
+    // Complementing the 5 input bits reverses the order of the 32 truth table entries
+    !rule_number.reverse_bits()
+}
+
+/// Symmetric variants of the rule under the symmetries preserved by the extender, along with the extended
+/// function of each variant computed from the extended function of the rule rather than by a new extension.
+pub fn symmetric_extensions<T: BooleanFunctionTester>(ca_extender: &CaExtender, rule_number: u32, extended_rule_number: &T::UnsignedRepr) -> Vec<(Symmetry, u32, T::UnsignedRepr)> {
+    Symmetry::preserved_by(ca_extender)
+        .into_iter()
+        .map(|symmetry| (symmetry, symmetry.apply_to_rule(rule_number), symmetry.apply_to_extension::<T>(extended_rule_number)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ca_extender::{BoundaryCondition, CaExtender};
+    use crate::u32_tester::U32Tester;
+    use crate::u512_tester::U512Tester;
+    use super::{conjugate_rule, mirror_rule, symmetric_extensions, Symmetry};
+
+    #[test]
+    fn test_symmetric_rules() {
+        // Leftmost neighbour rule becomes the rightmost neighbour rule, and is its own conjugate
+        assert_eq!(mirror_rule(0xffff0000), 0xaaaaaaaa);
+        assert_eq!(conjugate_rule(0xffff0000), 0xffff0000);
+        assert_eq!(conjugate_rule(0), u32::MAX);
+        for symmetry in Symmetry::ALL {
+            assert_eq!(symmetry.apply_to_rule(symmetry.apply_to_rule(0x8ccdda51)), 0x8ccdda51);
+        }
+        // Extension symmetries match the rule symmetries on the 5-variable functions themselves
+        for symmetry in Symmetry::ALL {
+            assert_eq!(symmetry.apply_to_extension::<U32Tester>(&0x8ccdda51), symmetry.apply_to_rule(0x8ccdda51));
+        }
+    }
+
+    #[test]
+    fn test_symmetric_extensions() {
+        for boundary_condition in BoundaryCondition::ALL {
+            let ca_extender = CaExtender::new(9, 2, 2, 4).with_boundary_condition(boundary_condition);
+            for rule_number in [0x8ccdda51, 3755921403] {
+                let extended_rule_number = ca_extender.extend::<U32Tester, U512Tester>(&rule_number);
+                for (_, symmetric_rule_number, symmetric_extended_rule_number) in symmetric_extensions::<U512Tester>(&ca_extender, rule_number, &extended_rule_number) {
+                    assert_eq!(ca_extender.extend::<U32Tester, U512Tester>(&symmetric_rule_number), symmetric_extended_rule_number);
+                }
+            }
+        }
+        assert_eq!(Symmetry::preserved_by(&CaExtender::new(9, 2, 2, 4).with_boundary_condition(BoundaryCondition::Fixed(true))), vec![Symmetry::Identity, Symmetry::Mirror]);
+        assert_eq!(Symmetry::preserved_by(&CaExtender::new(9, 2, 2, 0)), vec![Symmetry::Identity, Symmetry::Conjugation]);
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-use gen_combinations::CombinationIterator;
 use crate::BooleanFunctionTester;
 
 pub struct U32Tester;
@@ -8,7 +6,16 @@ impl BooleanFunctionTester for U32Tester {
     type UnsignedRepr = u32;
     const NUM_VARIABLES: usize = 5;
     const MAX_INPUT_VALUE: u32 = 2u32.pow(Self::NUM_VARIABLES as u32) - 1;
-    const MAX_FUNCTION_NUMBER: Self::UnsignedRepr = u32::MAX;
+
+    fn max_function_number() -> Self::UnsignedRepr {
+        u32::MAX
+    }
+
+    fn from_truth_table(truth_table: &[bool]) -> Self::UnsignedRepr {
+        truth_table.iter()
+            .enumerate()
+            .fold(0, |rule_number, (x, &value)| rule_number | ((value as u32) << x))
+    }
 
     fn fast_bool_anf_transform_unsigned(rule_number: &Self::UnsignedRepr, num_variables_function: usize) -> Self::UnsignedRepr {
         fast_boolean_anf_transform::fast_bool_anf_transform_unsigned(*rule_number, num_variables_function)
@@ -16,7 +23,7 @@ impl BooleanFunctionTester for U32Tester {
 
     fn get_function_degree(rule_number: &Self::UnsignedRepr) -> usize {
         let anf_form = Self::fast_bool_anf_transform_unsigned(rule_number, Self::NUM_VARIABLES);
-        (0..=Self::MAX_INPUT_VALUE).into_iter().map(|bit_position| {
+        (0..=Self::MAX_INPUT_VALUE).map(|bit_position| {
             if anf_form & (1 << bit_position) != 0 {
                 bit_position.count_ones() as usize
             } else {
@@ -25,16 +32,6 @@ impl BooleanFunctionTester for U32Tester {
         }).max().unwrap_or(0)
     }
 
-    fn is_strict_avalanche_criterion_ok(rule_number: &Self::UnsignedRepr) -> bool {
-        (0..Self::NUM_VARIABLES).into_iter().all(|constant_position| {
-            let constant = 1 << constant_position;
-            (0..=Self::MAX_INPUT_VALUE).into_iter().filter(|&x| {
-                let x_prime = x ^ constant;
-                Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
-            }).count() == (1 << (Self::NUM_VARIABLES - 1))
-        })
-    }
-
     fn compute_cellular_automata_rule(rule_number: &Self::UnsignedRepr, input_bits: u32) -> bool {
         #[cfg(debug_assertions)]
         if input_bits > Self::MAX_INPUT_VALUE {
@@ -48,71 +45,6 @@ impl BooleanFunctionTester for U32Tester {
         rule_number.count_ones() == EXPECTED_SET_NUMBER
     }
 
-    fn is_first_order_correlation_immune(rule_number: &Self::UnsignedRepr) -> bool {
-        (0..Self::NUM_VARIABLES)
-            .map(|input_bit_number| {
-                1 << input_bit_number
-            })
-            .all(|w| {
-                Self::fast_walsh_transform(rule_number, w) == 0
-            })
-    }
-
-    // https://doc.sagemath.org/html/en/reference/cryptography/sage/crypto/boolean_function.html#sage.crypto.boolean_function.BooleanFunction.walsh_hadamard_transform
-    fn fast_walsh_transform(rule_number: &Self::UnsignedRepr, w: u32) -> i32 {
-        (0..=Self::MAX_INPUT_VALUE).map(|x| {
-            if (Self::compute_cellular_automata_rule(rule_number, x) as u32 + Self::fast_binary_dot_product(w, x as u32)) & 1 == 0 { // % modulo 2
-                1
-            } else {
-                -1
-            }
-        }).sum()
-    }
-
-    fn absolute_walsh_spectrum(rule_number: &Self::UnsignedRepr) -> HashMap<u32, usize> {
-        let mut absolute_walsh_value_count_map: HashMap<u32, usize> = HashMap::new();
-        (0..=Self::MAX_INPUT_VALUE)
-            .for_each(|w| {
-                let absolute_walsh_value = Self::fast_walsh_transform(rule_number, w).unsigned_abs();
-                if !absolute_walsh_value_count_map.contains_key(&absolute_walsh_value) {
-                    absolute_walsh_value_count_map.insert(absolute_walsh_value, 1);
-                } else {
-                    let count = absolute_walsh_value_count_map.get_mut(&absolute_walsh_value).unwrap();
-                    *count += 1;
-                }
-            });
-        absolute_walsh_value_count_map
-    }
-
-    fn is_propagation_criterion_deg_k_ok(rule_number: &Self::UnsignedRepr, k: usize) -> bool {
-        if k == 0 {
-            return true;
-        }
-        let strict_avalanche_criterion_ok = Self::is_strict_avalanche_criterion_ok(rule_number);
-        if k == 1 {
-            return strict_avalanche_criterion_ok;
-        }
-        if k >= 1 && !strict_avalanche_criterion_ok {
-            return false;
-        }
-        let possible_reversable_bit_position = (0..Self::NUM_VARIABLES).into_iter().collect::<Vec<usize>>();
-        (2..=k).into_iter().all(|criterion_degree| {
-            //let possible_combinations_count = num_integer::binomial(self.input_dimension, criterion_degree);
-            CombinationIterator::new(&possible_reversable_bit_position, criterion_degree)
-                .all(|combination| {
-                    let mut bit_mask = 0;
-                    for &bit_position in combination {
-                        bit_mask |= (1 << bit_position) as u32;
-                    }
-                    let function_equal_mask_count = (0..=Self::MAX_INPUT_VALUE).into_iter().filter(|&x| {
-                        let x_prime = x ^ bit_mask;
-                        Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
-                    }).count();
-                    function_equal_mask_count == (1 << (Self::NUM_VARIABLES - 1))
-                })
-        })
-    }
-
     fn is_function_linear(rule_number: &Self::UnsignedRepr) -> bool {
         [*rule_number, Self::reverse_function(*rule_number)].iter().any(|rule| {
             let mut equivalent_xor_function: u32 = 0;
@@ -131,33 +63,8 @@ impl BooleanFunctionTester for U32Tester {
 }
 
 impl U32Tester {
-    pub fn fast_auto_correlation_transform(rule_number: &u32, w: u32) -> i32 {
-        (0..=Self::MAX_INPUT_VALUE).map(|x| {
-            if Self::compute_cellular_automata_rule(rule_number, x) ^ Self::compute_cellular_automata_rule(rule_number, x ^ w) {
-                -1
-            } else {
-                1
-            }
-        }).sum()
-    }
-
-    pub fn absolute_autocorrelation_spectrum(rule_number: &u32) -> HashMap<u32, usize> {
-        let mut absolute_autocorrelation_value_count_map: HashMap<u32, usize> = HashMap::new();
-        (0..=Self::MAX_INPUT_VALUE)
-            .for_each(|w| {
-                let absolute_autocorrelation_value = Self::fast_auto_correlation_transform(rule_number, w).unsigned_abs();
-                if !absolute_autocorrelation_value_count_map.contains_key(&absolute_autocorrelation_value) {
-                    absolute_autocorrelation_value_count_map.insert(absolute_autocorrelation_value, 1);
-                } else {
-                    let count = absolute_autocorrelation_value_count_map.get_mut(&absolute_autocorrelation_value).unwrap();
-                    *count += 1;
-                }
-            });
-        absolute_autocorrelation_value_count_map
-    }
-
     fn reverse_function(rule_number: u32) -> u32 {
-        !rule_number & Self::MAX_FUNCTION_NUMBER
+        !rule_number & Self::max_function_number()
     }
 }
 
@@ -182,6 +89,77 @@ mod tests {
         assert_eq!(super::U32Tester::absolute_walsh_spectrum(&4294967295), HashMap::from([(32, 1), (0, 31)]));
     }
 
+    #[test]
+    fn test_walsh_spectrum() {
+        for rule_number in [0, 3755921403, 3755921407, 0x96696996] {
+            let walsh_spectrum = super::U32Tester::walsh_spectrum(&rule_number);
+            assert_eq!(walsh_spectrum.len(), 32);
+            for w in 0..32u32 {
+                let expected_walsh_value: i32 = (0..32u32).map(|x| {
+                    if ((rule_number >> x) & 1) ^ ((w & x).count_ones() & 1) == 0 { 1 } else { -1 }
+                }).sum();
+                assert_eq!(walsh_spectrum[w as usize], expected_walsh_value);
+            }
+        }
+        assert_eq!(super::U32Tester::walsh_spectrum(&0x96696996)[31], 32);
+    }
+
+    #[test]
+    fn test_nonlinearity() {
+        assert_eq!(super::U32Tester::nonlinearity(&0), 0);
+        assert_eq!(super::U32Tester::nonlinearity(&3755921403), 6);
+        assert_eq!(super::U32Tester::nonlinearity(&3755921407), 5);
+        assert_eq!(super::U32Tester::nonlinearity(&4294967295), 0);
+    }
+
+    #[test]
+    fn test_correlation_immunity_and_resiliency_order() {
+        assert_eq!(super::U32Tester::correlation_immunity_order(&0), 5);
+        assert_eq!(super::U32Tester::resiliency_order(&0), None);
+        assert_eq!(super::U32Tester::correlation_immunity_order(&3755921403), 0);
+        assert_eq!(super::U32Tester::resiliency_order(&3755921403), None);
+        assert_eq!(super::U32Tester::correlation_immunity_order(&0x66666666), 1);
+        assert_eq!(super::U32Tester::resiliency_order(&0x66666666), Some(1));
+        assert_eq!(super::U32Tester::correlation_immunity_order(&0x96696996), 4);
+        assert_eq!(super::U32Tester::resiliency_order(&0x96696996), Some(4));
+    }
+
+    #[test]
+    fn test_algebraic_immunity() {
+        assert_eq!(super::U32Tester::algebraic_immunity(&0), (0, 4294967295));
+        assert_eq!(super::U32Tester::algebraic_immunity(&0x96696996).0, 1);
+        let (algebraic_immunity, annihilator) = super::U32Tester::algebraic_immunity(&0xfee8e880);
+        assert_eq!(algebraic_immunity, 3);
+        assert_ne!(annihilator, 0);
+        assert!(annihilator & 0xfee8e880 == 0 || annihilator & !0xfee8e880 == 0);
+        assert!(super::U32Tester::get_function_degree(&annihilator) <= 3);
+    }
+
+    #[test]
+    fn test_global_avalanche_characteristics() {
+        assert_eq!(super::U32Tester::absolute_indicator(&0), 32);
+        assert_eq!(super::U32Tester::sum_of_squares_indicator(&0), 32768);
+        assert_eq!(super::U32Tester::absolute_indicator(&3755921403), 16);
+        assert_eq!(super::U32Tester::sum_of_squares_indicator(&3755921403), 5888);
+        assert_eq!(super::U32Tester::absolute_indicator(&3755921407), 20);
+        assert_eq!(super::U32Tester::sum_of_squares_indicator(&3755921407), 8048);
+    }
+
+    #[test]
+    fn test_bent_and_plateaued() {
+        assert!(!super::U32Tester::is_bent(&0x87777888));
+        assert!(super::U32Tester::is_semi_bent(&0x87777888));
+        assert_eq!(super::U32Tester::plateau_amplitude(&0x87777888), Some(8));
+        assert!(!super::U32Tester::is_plateaued(&3755921403));
+    }
+
+    #[test]
+    fn test_linear_structures() {
+        assert_eq!(super::U32Tester::linear_structures(&0x78787878), vec![(4, true), (8, false), (12, true), (16, false), (20, true), (24, false), (28, true)]);
+        assert_eq!(super::U32Tester::linear_structures(&0).len(), 31);
+        assert!(super::U32Tester::linear_structures(&3755921403).is_empty());
+    }
+
     #[test]
     fn test_absolute_autocorrelation_spectrum() {
         assert_eq!(super::U32Tester::absolute_autocorrelation_spectrum(&0), HashMap::from([(32, 32)]));
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-use gen_combinations::CombinationIterator;
 use numext_fixed_uint::U512;
 use crate::BooleanFunctionTester;
 
@@ -10,7 +8,17 @@ impl BooleanFunctionTester for U512Tester {
     const NUM_VARIABLES: usize = 9;
 
     const MAX_INPUT_VALUE: u32 = 2u32.pow(Self::NUM_VARIABLES as u32) - 1;
-    const MAX_FUNCTION_NUMBER: Self::UnsignedRepr = U512::max_value();
+
+    fn max_function_number() -> Self::UnsignedRepr {
+        U512::max_value()
+    }
+
+    fn from_truth_table(truth_table: &[bool]) -> Self::UnsignedRepr {
+        truth_table.iter()
+            .enumerate()
+            .filter(|(_, &value)| value)
+            .fold(U512::zero(), |rule_number, (x, _)| rule_number | (U512::one() << x))
+    }
 
     fn fast_bool_anf_transform_unsigned(rule_number: &Self::UnsignedRepr, num_variables_function: usize) -> Self::UnsignedRepr {
         const U0: U512 = U512::zero();
@@ -26,21 +34,21 @@ impl BooleanFunctionTester for U512Tester {
                     let f_source_i: bool = ((final_f.clone() >> (source + i)) & U512::one()) != U0;
                     let f_target_i_xor_f_source_i = f_target_i ^ f_source_i;
                     if f_target_i_xor_f_source_i {
-                        final_f = final_f | (U512::one() << (target.clone() + i));
+                        final_f |= U512::one() << (target + i);
                     } else {
-                        final_f = final_f & !(U512::one() << (target.clone() + i));
+                        final_f &= !(U512::one() << (target + i));
                     }
                 }
-                source = source.clone() + (blocksize << 1);
+                source += blocksize << 1;
             }
-            blocksize = blocksize << 1;
+            blocksize <<= 1;
         }
         final_f
     }
 
     fn get_function_degree(rule_number: &Self::UnsignedRepr) -> usize {
         let anf_form = Self::fast_bool_anf_transform_unsigned(rule_number, Self::NUM_VARIABLES);
-        (0..=Self::MAX_INPUT_VALUE).into_iter().map(|bit_position| {
+        (0..=Self::MAX_INPUT_VALUE).map(|bit_position| {
             if anf_form.clone() & (U512::one() << bit_position) != U512::zero() {
                 bit_position.count_ones() as usize
             } else {
@@ -49,16 +57,6 @@ impl BooleanFunctionTester for U512Tester {
         }).max().unwrap_or(0)
     }
 
-    fn is_strict_avalanche_criterion_ok(rule_number: &Self::UnsignedRepr) -> bool {
-        (0..Self::NUM_VARIABLES).into_iter().all(|constant_position| {
-            let constant = 1 << constant_position;
-            (0..=Self::MAX_INPUT_VALUE).into_iter().filter(|&x| {
-                let x_prime = x ^ constant;
-                Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
-            }).count() == (1 << (Self::NUM_VARIABLES - 1))
-        })
-    }
-
     fn compute_cellular_automata_rule(rule_number: &Self::UnsignedRepr, input_bits: u32) -> bool {
         #[cfg(debug_assertions)]
         if input_bits > Self::MAX_INPUT_VALUE {
@@ -72,70 +70,6 @@ impl BooleanFunctionTester for U512Tester {
         rule_number.count_ones() as i32 == EXPECTED_SET_NUMBER
     }
 
-    fn is_first_order_correlation_immune(rule_number: &Self::UnsignedRepr) -> bool {
-        (0..Self::NUM_VARIABLES)
-            .map(|input_bit_number| {
-                1 << input_bit_number
-            })
-            .all(|w| {
-                Self::fast_walsh_transform(rule_number, w) == 0
-            })
-    }
-
-    fn fast_walsh_transform(rule_number: &Self::UnsignedRepr, w: u32) -> i32 {
-        (0..=Self::MAX_INPUT_VALUE).map(|x| {
-            if (Self::compute_cellular_automata_rule(rule_number, x) as u32 + Self::fast_binary_dot_product(w, x as u32)) & 1 == 0 { // % modulo 2
-                1
-            } else {
-                -1
-            }
-        }).sum()
-    }
-
-    fn absolute_walsh_spectrum(rule_number: &Self::UnsignedRepr) -> HashMap<u32, usize> {
-        let mut absolute_walsh_value_count_map: HashMap<u32, usize> = HashMap::new();
-        (0..=Self::MAX_INPUT_VALUE)
-            .for_each(|w| {
-                let absolute_walsh_value = Self::fast_walsh_transform(rule_number, w).unsigned_abs();
-                if !absolute_walsh_value_count_map.contains_key(&absolute_walsh_value) {
-                    absolute_walsh_value_count_map.insert(absolute_walsh_value, 1);
-                } else {
-                    let count = absolute_walsh_value_count_map.get_mut(&absolute_walsh_value).unwrap();
-                    *count += 1;
-                }
-            });
-        absolute_walsh_value_count_map
-    }
-
-    fn is_propagation_criterion_deg_k_ok(rule_number: &Self::UnsignedRepr, k: usize) -> bool {
-        if k == 0 {
-            return true;
-        }
-        let strict_avalanche_criterion_ok = Self::is_strict_avalanche_criterion_ok(rule_number);
-        if k == 1 {
-            return strict_avalanche_criterion_ok;
-        }
-        if k >= 1 && !strict_avalanche_criterion_ok {
-            return false;
-        }
-        let possible_reversable_bit_position = (0..Self::NUM_VARIABLES).into_iter().collect::<Vec<usize>>();
-        (2..=k).into_iter().all(|criterion_degree| {
-            //let possible_combinations_count = num_integer::binomial(self.input_dimension, criterion_degree);
-            CombinationIterator::new(&possible_reversable_bit_position, criterion_degree)
-                .all(|combination| {
-                    let mut bit_mask = 0;
-                    for &bit_position in combination {
-                        bit_mask |= (1 << bit_position) as u32;
-                    }
-                    let function_equal_mask_count = (0..=Self::MAX_INPUT_VALUE).into_iter().filter(|&x| {
-                        let x_prime = x ^ bit_mask;
-                        Self::compute_cellular_automata_rule(rule_number, x) == Self::compute_cellular_automata_rule(rule_number, x_prime)
-                    }).count();
-                    function_equal_mask_count == (1 << (Self::NUM_VARIABLES - 1))
-                })
-        })
-    }
-
     fn is_function_linear(rule_number: &Self::UnsignedRepr) -> bool {
         [rule_number.clone(), Self::reverse_function(rule_number)].iter().any(|rule| {
             let mut equivalent_xor_function: U512 = U512::zero();
@@ -155,7 +89,7 @@ impl BooleanFunctionTester for U512Tester {
 
 impl U512Tester {
     fn reverse_function(rule_number: &U512) -> U512 {
-        !rule_number & Self::MAX_FUNCTION_NUMBER
+        !rule_number & Self::max_function_number()
     }
 }
 
@@ -180,4 +114,75 @@ mod tests {
         assert_eq!(super::U512Tester::absolute_walsh_spectrum(&U512::from_dec_str("13407807929942597098847186317233203207567774193617110470605594638121991820277051203989568509643346170367187143529348261601759818256683210860310861684080895").unwrap()), HashMap::from([(352, 1), (0, 480), (32, 20), (96, 10), (160, 1)]));
         assert_eq!(super::U512Tester::absolute_walsh_spectrum(&U512::max_value()), HashMap::from([(512, 1), (0, 511)]));
     }
+
+    #[test]
+    fn test_nonlinearity() {
+        assert_eq!(super::U512Tester::nonlinearity(&U512::zero()), 0);
+        assert_eq!(super::U512Tester::nonlinearity(&U512::from_dec_str("13407807929942597098847186317233203207567774193617110470060917943962159749266320668916332720419656517166240687192852651607093788182615166684716733579591935").unwrap()), 96);
+        assert_eq!(super::U512Tester::nonlinearity(&U512::from_dec_str("13407807929942597098847186317233203207567774193617110470605594638121991820277051203989568509643346170367187143529348261601759818256683210860310861684080895").unwrap()), 80);
+        assert_eq!(super::U512Tester::nonlinearity(&U512::max_value()), 0);
+    }
+
+    #[test]
+    fn test_correlation_immunity_and_resiliency_order() {
+        let parity_function = (0..512u32)
+            .filter(|x| x.count_ones() % 2 == 1)
+            .fold(U512::zero(), |function, x| function | (U512::one() << x));
+        assert_eq!(super::U512Tester::correlation_immunity_order(&U512::zero()), 9);
+        assert_eq!(super::U512Tester::resiliency_order(&U512::zero()), None);
+        assert_eq!(super::U512Tester::correlation_immunity_order(&U512::from_dec_str("13407807929942597098847186317233203207567774193617110470060917943962159749266320668916332720419656517166240687192852651607093788182615166684716733579591935").unwrap()), 0);
+        assert_eq!(super::U512Tester::correlation_immunity_order(&parity_function), 8);
+        assert_eq!(super::U512Tester::resiliency_order(&parity_function), Some(8));
+    }
+
+    #[test]
+    fn test_algebraic_immunity() {
+        let majority_function = (0..512u32)
+            .filter(|x| x.count_ones() >= 5)
+            .fold(U512::zero(), |function, x| function | (U512::one() << x));
+        assert_eq!(super::U512Tester::algebraic_immunity(&U512::zero()), (0, U512::max_value()));
+        let (algebraic_immunity, annihilator) = super::U512Tester::algebraic_immunity(&majority_function);
+        assert_eq!(algebraic_immunity, 5);
+        assert_ne!(annihilator, U512::zero());
+        assert!(annihilator.clone() & majority_function.clone() == U512::zero() || annihilator.clone() & !majority_function == U512::zero());
+        assert!(super::U512Tester::get_function_degree(&annihilator) <= 5);
+    }
+
+    #[test]
+    fn test_autocorrelation_spectrum() {
+        let rule_number = U512::from_dec_str("13407807929942597098847186317233203207567774193617110470605594638121991820277051203989568509643346170367187143529348261601759818256683210860310861684080895").unwrap();
+        let autocorrelation_spectrum = super::U512Tester::autocorrelation_spectrum(&rule_number);
+        for w in [0u32, 1, 17, 255, 256, 511] {
+            let expected_autocorrelation_value: i32 = (0..512u32).map(|x| {
+                if super::U512Tester::compute_cellular_automata_rule(&rule_number, x) ^ super::U512Tester::compute_cellular_automata_rule(&rule_number, x ^ w) { -1 } else { 1 }
+            }).sum();
+            assert_eq!(autocorrelation_spectrum[w as usize], expected_autocorrelation_value);
+            assert_eq!(super::U512Tester::fast_auto_correlation_transform(&rule_number, w), expected_autocorrelation_value);
+        }
+        assert_eq!(super::U512Tester::absolute_autocorrelation_spectrum(&U512::zero()), HashMap::from([(512, 512)]));
+    }
+
+    #[test]
+    fn test_global_avalanche_characteristics() {
+        assert_eq!(super::U512Tester::absolute_indicator(&U512::zero()), 512);
+        assert_eq!(super::U512Tester::sum_of_squares_indicator(&U512::zero()), 1 << 27);
+        let rule_number = U512::from_dec_str("13407807929942597098847186317233203207567774193617110470605594638121991820277051203989568509643346170367187143529348261601759818256683210860310861684080895").unwrap();
+        let autocorrelation_spectrum = super::U512Tester::autocorrelation_spectrum(&rule_number);
+        assert_eq!(super::U512Tester::absolute_indicator(&rule_number), autocorrelation_spectrum[1..].iter().map(|r| r.unsigned_abs()).max().unwrap());
+        assert_eq!(super::U512Tester::sum_of_squares_indicator(&rule_number), autocorrelation_spectrum.iter().map(|&r| (r as i64 * r as i64) as u64).sum::<u64>());
+    }
+
+    #[test]
+    fn test_linear_structures() {
+        let first_variable_function = (0..512u32)
+            .filter(|x| x & 1 == 1)
+            .fold(U512::zero(), |function, x| function | (U512::one() << x));
+        let linear_structures = super::U512Tester::linear_structures(&first_variable_function);
+        assert_eq!(linear_structures.len(), 511);
+        assert!(linear_structures.iter().all(|&(a, derivative)| derivative == (a & 1 == 1)));
+        let majority_function = (0..512u32)
+            .filter(|x| x.count_ones() >= 5)
+            .fold(U512::zero(), |function, x| function | (U512::one() << x));
+        assert_eq!(super::U512Tester::linear_structures(&majority_function), vec![(511, true)]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use crate::{walsh_hadamard_butterfly, BooleanFunctionTester};
+
+/// Vectorial Boolean function F: GF(2)^num_inputs -> GF(2)^num_outputs (an S-box), stored as a lookup table
+/// indexed by the input, bit i of an output value being the coordinate function i.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorialFunction {
+    num_inputs: usize,
+    num_outputs: usize,
+    lookup_table: Vec<u32>,
+}
+
+impl VectorialFunction {
+    pub fn new(num_inputs: usize, num_outputs: usize, lookup_table: Vec<u32>) -> Self {
+        if num_inputs == 0 || num_inputs > 16 || num_outputs == 0 || num_outputs > 16 {
+            panic!("Number of inputs and outputs must be between 1 and 16");
+        }
+        if lookup_table.len() != 1 << num_inputs {
+            panic!("Lookup table must have {} entries", 1 << num_inputs);
+        }
+        if lookup_table.iter().any(|&output| output >> num_outputs != 0) {
+            panic!("Lookup table values must be less than {}", 1 << num_outputs);
+        }
+        VectorialFunction {
+            num_inputs,
+            num_outputs,
+            lookup_table,
+        }
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn num_outputs(&self) -> usize {
+        self.num_outputs
+    }
+
+    pub fn lookup_table(&self) -> &[u32] {
+        &self.lookup_table
+    }
+
+    pub fn evaluate(&self, x: u32) -> u32 {
+        self.lookup_table[x as usize]
+    }
+
+    /// Coordinate function F_i, as the rule_number representation of the tester T of num_inputs variables.
+    pub fn coordinate_function<T: BooleanFunctionTester>(&self, i: usize) -> T::UnsignedRepr {
+        if i >= self.num_outputs {
+            panic!("Coordinate must be less than {}", self.num_outputs);
+        }
+        self.component_function::<T>(1 << i)
+    }
+
+    /// Component function b.F, as the rule_number representation of the tester T of num_inputs variables.
+    pub fn component_function<T: BooleanFunctionTester>(&self, b: u32) -> T::UnsignedRepr {
+        if T::NUM_VARIABLES != self.num_inputs {
+            panic!("Tester must have {} variables", self.num_inputs);
+        }
+        T::from_truth_table(&self.component_truth_table(b))
+    }
+
+    /// DDT[a][b] = #{x | F(x) + F(x + a) = b}, for input differences a and output differences b.
+    pub fn difference_distribution_table(&self) -> Vec<Vec<u32>> {
+        (0..1u32 << self.num_inputs).map(|a| {
+            let mut ddt_row = vec![0u32; 1 << self.num_outputs];
+            for x in 0..1u32 << self.num_inputs {
+                ddt_row[(self.evaluate(x) ^ self.evaluate(x ^ a)) as usize] += 1;
+            }
+            ddt_row
+        }).collect()
+    }
+
+    /// Max of DDT[a][b] over nonzero a.
+    pub fn differential_uniformity(&self) -> u32 {
+        self.difference_distribution_table()
+            .iter()
+            .skip(1)
+            .flat_map(|ddt_row| ddt_row.iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Number of occurrences of each DDT value over nonzero input differences.
+    pub fn differential_spectrum(&self) -> HashMap<u32, usize> {
+        let mut ddt_value_count_map: HashMap<u32, usize> = HashMap::new();
+        self.difference_distribution_table()
+            .iter()
+            .skip(1)
+            .flat_map(|ddt_row| ddt_row.iter())
+            .for_each(|&ddt_value| {
+                *ddt_value_count_map.entry(ddt_value).or_insert(0) += 1;
+            });
+        ddt_value_count_map
+    }
+
+    /// Almost perfect nonlinear: differential uniformity 2, the lowest possible.
+    pub fn is_apn(&self) -> bool {
+        self.differential_uniformity() == 2
+    }
+
+    /// LAT[a][b] = W_{b.F}(a) = sum_x (-1)^(b.F(x) + a.x), the Walsh transform of every component function.
+    pub fn linear_approximation_table(&self) -> Vec<Vec<i32>> {
+        let component_walsh_spectra = (0..1u32 << self.num_outputs).map(|b| {
+            let mut walsh_spectrum = self.component_truth_table(b)
+                .iter()
+                .map(|&value| if value { -1 } else { 1 })
+                .collect::<Vec<i32>>();
+            walsh_hadamard_butterfly(&mut walsh_spectrum);
+            walsh_spectrum
+        }).collect::<Vec<Vec<i32>>>();
+        (0..1usize << self.num_inputs).map(|a| {
+            component_walsh_spectra.iter().map(|walsh_spectrum| walsh_spectrum[a]).collect()
+        }).collect()
+    }
+
+    /// Max of |LAT[a][b]| over nonzero b.
+    pub fn linearity(&self) -> u32 {
+        self.linear_approximation_table()
+            .iter()
+            .flat_map(|lat_row| lat_row.iter().skip(1))
+            .map(|walsh_value| walsh_value.unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Min of the nonlinearities of the nonzero component functions.
+    pub fn nonlinearity(&self) -> u32 {
+        (1 << (self.num_inputs - 1)) - self.linearity() / 2
+    }
+
+    /// Max of the algebraic degrees of the coordinate functions.
+    pub fn algebraic_degree(&self) -> usize {
+        (0..self.num_outputs).map(|i| {
+            // Möbius transform of the coordinate function truth table
+            let mut anf_form = self.component_truth_table(1 << i);
+            for variable in 0..self.num_inputs {
+                for x in (0..1usize << self.num_inputs).filter(|x| x & (1 << variable) != 0) {
+                    anf_form[x] ^= anf_form[x ^ (1 << variable)];
+                }
+            }
+            anf_form.iter()
+                .enumerate()
+                .filter(|(_, &coefficient)| coefficient)
+                .map(|(monomial, _)| monomial.count_ones() as usize)
+                .max()
+                .unwrap_or(0)
+        }).max().unwrap_or(0)
+    }
+
+    pub fn is_permutation(&self) -> bool {
+        if self.num_inputs != self.num_outputs {
+            return false;
+        }
+        let mut reached_outputs = vec![false; 1 << self.num_outputs];
+        self.lookup_table.iter().all(|&output| !std::mem::replace(&mut reached_outputs[output as usize], true))
+    }
+
+    pub fn inverse(&self) -> Option<VectorialFunction> {
+        if !self.is_permutation() {
+            return None;
+        }
+        let mut inverse_lookup_table = vec![0u32; 1 << self.num_inputs];
+        for (x, &output) in self.lookup_table.iter().enumerate() {
+            inverse_lookup_table[output as usize] = x as u32;
+        }
+        Some(VectorialFunction::new(self.num_inputs, self.num_outputs, inverse_lookup_table))
+    }
+
+    /// BCT[a][b] = #{x | F^-1(F(x) + b) + F^-1(F(x + a) + b) = a}, only defined for permutations.
+    pub fn boomerang_connectivity_table(&self) -> Option<Vec<Vec<u32>>> {
+        let inverse = self.inverse()?;
+        Some((0..1u32 << self.num_inputs).map(|a| {
+            (0..1u32 << self.num_outputs).map(|b| {
+                (0..1u32 << self.num_inputs).filter(|&x| {
+                    inverse.evaluate(self.evaluate(x) ^ b) ^ inverse.evaluate(self.evaluate(x ^ a) ^ b) == a
+                }).count() as u32
+            }).collect()
+        }).collect())
+    }
+
+    /// Max of BCT[a][b] over nonzero a and b, only defined for permutations.
+    pub fn boomerang_uniformity(&self) -> Option<u32> {
+        let boomerang_connectivity_table = self.boomerang_connectivity_table()?;
+        Some(boomerang_connectivity_table
+            .iter()
+            .skip(1)
+            .flat_map(|bct_row| bct_row.iter().skip(1))
+            .copied()
+            .max()
+            .unwrap_or(0))
+    }
+
+    pub(crate) fn component_truth_table(&self, b: u32) -> Vec<bool> {
+        self.lookup_table.iter()
+            .map(|&output| (output & b).count_ones() % 2 == 1)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::BooleanFunctionTester;
+    use crate::generic_tester::GenericTester;
+    use crate::u32_tester::U32Tester;
+    use super::VectorialFunction;
+
+    const CUBE_GF8: [u32; 8] = [0, 1, 3, 4, 5, 6, 7, 2];
+    const PRESENT_SBOX: [u32; 16] = [0xc, 0x5, 0x6, 0xb, 0x9, 0x0, 0xa, 0xd, 0x3, 0xe, 0xf, 0x8, 0x4, 0x7, 0x1, 0x2];
+
+    #[test]
+    fn test_coordinate_and_component_functions() {
+        // F(x) = (x0 x1, x0 + x4)
+        let vectorial_function = VectorialFunction::new(5, 2, (0..32u32).map(|x| (x & x >> 1 & 1) | ((x ^ x >> 4) & 1) << 1).collect());
+        assert_eq!(vectorial_function.evaluate(0b10011), 0b01);
+        assert_eq!(vectorial_function.coordinate_function::<U32Tester>(0), 0x88888888);
+        assert_eq!(vectorial_function.coordinate_function::<U32Tester>(1), 0x5555aaaa);
+        assert_eq!(vectorial_function.component_function::<U32Tester>(0b11), 0x5555aaaa ^ 0x88888888);
+        assert_eq!(U32Tester::get_function_degree(&vectorial_function.component_function::<U32Tester>(0b11)), 2);
+        assert_eq!(vectorial_function.component_function::<U32Tester>(0), 0);
+    }
+
+    #[test]
+    fn test_differential_properties() {
+        let cube = VectorialFunction::new(3, 3, CUBE_GF8.to_vec());
+        assert_eq!(cube.difference_distribution_table()[1], vec![0, 2, 0, 2, 0, 2, 0, 2]);
+        assert_eq!(cube.differential_uniformity(), 2);
+        assert_eq!(cube.differential_spectrum(), HashMap::from([(0, 28), (2, 28)]));
+        assert!(cube.is_apn());
+
+        let present_sbox = VectorialFunction::new(4, 4, PRESENT_SBOX.to_vec());
+        assert_eq!(present_sbox.difference_distribution_table()[1], vec![0, 0, 0, 4, 0, 0, 0, 4, 0, 4, 0, 0, 0, 4, 0, 0]);
+        assert_eq!(present_sbox.differential_uniformity(), 4);
+        assert_eq!(present_sbox.differential_spectrum(), HashMap::from([(0, 144), (2, 72), (4, 24)]));
+        assert!(!present_sbox.is_apn());
+        assert_eq!(present_sbox.difference_distribution_table()[0][0], 16);
+    }
+
+    #[test]
+    fn test_linear_properties() {
+        let cube = VectorialFunction::new(3, 3, CUBE_GF8.to_vec());
+        assert_eq!(cube.linear_approximation_table()[1], vec![0, -4, 0, 4, 0, 4, 0, 4]);
+        assert_eq!(cube.linear_approximation_table()[0][0], 8);
+        assert_eq!(cube.linearity(), 4);
+        assert_eq!(cube.nonlinearity(), 2);
+
+        let present_sbox = VectorialFunction::new(4, 4, PRESENT_SBOX.to_vec());
+        assert_eq!(present_sbox.linear_approximation_table()[1], vec![0, 0, 0, 0, 0, -8, 0, -8, 0, 0, 0, 0, 0, -8, 0, 8]);
+        assert_eq!(present_sbox.linearity(), 8);
+        assert_eq!(present_sbox.nonlinearity(), 4);
+        for b in 1..16 {
+            let component_function = present_sbox.component_function::<GenericTester<4>>(b);
+            assert!(GenericTester::<4>::nonlinearity(&component_function) >= present_sbox.nonlinearity());
+        }
+    }
+
+    #[test]
+    fn test_algebraic_degree() {
+        assert_eq!(VectorialFunction::new(3, 3, CUBE_GF8.to_vec()).algebraic_degree(), 2);
+        assert_eq!(VectorialFunction::new(4, 4, PRESENT_SBOX.to_vec()).algebraic_degree(), 3);
+        assert_eq!(VectorialFunction::new(4, 4, (0..16).collect()).algebraic_degree(), 1);
+        assert_eq!(VectorialFunction::new(2, 1, vec![0; 4]).algebraic_degree(), 0);
+    }
+
+    #[test]
+    fn test_boomerang_properties() {
+        let cube = VectorialFunction::new(3, 3, CUBE_GF8.to_vec());
+        assert_eq!(cube.boomerang_connectivity_table().unwrap()[1], vec![8, 2, 0, 2, 0, 2, 0, 2]);
+        assert_eq!(cube.boomerang_uniformity(), Some(2));
+
+        let present_sbox = VectorialFunction::new(4, 4, PRESENT_SBOX.to_vec());
+        assert!(present_sbox.is_permutation());
+        assert_eq!(present_sbox.inverse().unwrap().lookup_table(), &[5, 14, 15, 8, 12, 1, 2, 13, 11, 4, 6, 3, 0, 7, 9, 10]);
+        assert_eq!(present_sbox.boomerang_connectivity_table().unwrap()[1], vec![16, 0, 4, 4, 0, 16, 4, 4, 4, 4, 0, 0, 4, 4, 0, 0]);
+        assert_eq!(present_sbox.boomerang_uniformity(), Some(16));
+
+        let not_permutation = VectorialFunction::new(2, 2, vec![0, 1, 1, 3]);
+        assert!(!not_permutation.is_permutation());
+        assert_eq!(not_permutation.inverse(), None);
+        assert_eq!(not_permutation.boomerang_uniformity(), None);
+        assert!(!VectorialFunction::new(2, 3, vec![0, 1, 2, 3]).is_permutation());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_lookup_table() {
+        VectorialFunction::new(2, 1, vec![0, 1, 2, 1]);
+    }
+}